@@ -183,7 +183,7 @@ where
 				</div>
 			</wu-debug-console-watermark>
 			<wu-debug-console class="contents">
-				<Modal class="vertical gap-2 border bg-surface-1 border-surface-2 shadow-lg rounded-md" open=open_debug_console>
+				<Modal class="vertical gap-2 border bg-surface-1 border-surface-2 shadow-lg rounded-md" open=open_debug_console set_open=open_debug_console>
 					<h1 class="text-2xl font-bold text-center">
 						"Debugger"
 					</h1>
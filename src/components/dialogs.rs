@@ -0,0 +1,133 @@
+//! Awaitable dialogs built on top of [`ModalHook`](crate::ModalHook).
+
+use std::sync::{Arc, Mutex};
+
+use futures::channel::oneshot;
+use leptos::prelude::*;
+
+use crate::{components::{AddModal, ModalHandle}, utils::Text};
+
+/// Opens a confirmation dialog with default labels.
+///
+/// Resolves to `true` if the user confirmed, or `false` if the user cancelled or closed the dialog.
+///
+/// # Note
+/// The dialog is opened immediately, not when the future is first polled.
+///
+/// # Example
+/// ```rust,ignore
+/// let add_modal = expect_context::<wu::AddModal<Main>>();
+/// let (delete, errors) = wu::actionize(add_modal, move |add_modal, id: u64| async move {
+///     if !wu::confirm(add_modal, move || "Delete the record?").await {
+///         return Ok(());
+///     }
+///     api::delete(id).await
+/// });
+/// ```
+pub fn confirm<M>(add_modal: AddModal<M>, message: impl Into<ViewFn>) -> impl std::future::Future<Output = bool> + Send + 'static
+where
+	M: Send + Sync + 'static,
+{
+	confirm_with(add_modal, message, "Confirm", "Cancel")
+}
+
+/// Opens a confirmation dialog with custom labels.
+///
+/// Resolves to `true` if the user confirmed, or `false` if the user cancelled or closed the dialog.
+///
+/// # Note
+/// The dialog is opened immediately, not when the future is first polled.
+pub fn confirm_with<M>(
+	add_modal: AddModal<M>,
+	message: impl Into<ViewFn>,
+	confirm_label: impl Into<Text>,
+	cancel_label: impl Into<Text>,
+) -> impl std::future::Future<Output = bool> + Send + 'static
+where
+	M: Send + Sync + 'static,
+{
+	let message = message.into();
+	let confirm_label = confirm_label.into();
+	let cancel_label = cancel_label.into();
+	let (tx, rx) = oneshot::channel::<bool>();
+	let tx = Arc::new(Mutex::new(Some(tx)));
+
+	// the sender lives inside the modal's view, so closing the modal in any way cancels the dialog
+	add_modal.run(ViewFn::from(move || {
+		let modal_handle = expect_context::<ModalHandle>();
+		let tx = tx.clone();
+
+		view! {
+			<div class="vertical gap-4">
+				<div>
+					{message.run()}
+				</div>
+				<div class="horizontal hend gap-2">
+					<button on:click=move |_| modal_handle.close() class="btn">
+						{cancel_label}
+					</button>
+					<button
+						on:click=move |_| {
+							if let Some(tx) = tx.lock().unwrap().take() {
+								_ = tx.send(true);
+							}
+							modal_handle.close();
+						}
+						class="btn btn-primary"
+					>
+						{confirm_label}
+					</button>
+				</div>
+			</div>
+		}
+	}));
+
+	async move { rx.await.unwrap_or(false) }
+}
+
+/// Opens a dialog containing a user-provided form.
+///
+/// The form receives a submit callback which resolves the dialog with the provided value.
+/// Resolves to `None` if the dialog was closed without submitting.
+///
+/// # Note
+/// The dialog is opened immediately, not when the future is first polled.
+/// To cancel from within the form, close the modal using the [`ModalHandle`] context.
+///
+/// # Example
+/// ```rust,ignore
+/// let add_modal = expect_context::<wu::AddModal<Main>>();
+/// let name = wu::prompt::<Main, String>(add_modal, move |submit: Callback<String>| {
+///     let value = RwSignal::new(String::default());
+///     view! {
+///         <form on:submit=move |ev| { ev.prevent_default(); submit.run(value.get()); }>
+///             <input type="text" bind:value=value />
+///         </form>
+///     }.into_any()
+/// }).await;
+/// ```
+pub fn prompt<M, T>(add_modal: AddModal<M>, form: impl Into<Callback<Callback<T>, AnyView>>) -> impl std::future::Future<Output = Option<T>> + Send + 'static
+where
+	M: Send + Sync + 'static,
+	T: Send + Sync + 'static,
+{
+	let form = form.into();
+	let (tx, rx) = oneshot::channel::<T>();
+	let tx = Arc::new(Mutex::new(Some(tx)));
+
+	// the sender lives inside the modal's view, so closing the modal in any way cancels the dialog
+	add_modal.run(ViewFn::from(move || {
+		let modal_handle = expect_context::<ModalHandle>();
+		let tx = tx.clone();
+		let submit = Callback::new(move |value: T| {
+			if let Some(tx) = tx.lock().unwrap().take() {
+				_ = tx.send(value);
+			}
+			modal_handle.close();
+		});
+
+		form.run(submit)
+	}));
+
+	async move { rx.await.ok() }
+}
@@ -1,6 +1,30 @@
-use leptos::{html, prelude::*};
+use leptos::{either::Either, html, prelude::*};
+use leptos_use::{
+	core::{PointerType, Position as PointerPosition},
+	use_draggable_with_options, UseDraggableOptions, UseDraggableReturn,
+};
 
-use crate::utils::{Position, Text};
+use crate::utils::{get_from_local_storage, push_focus_trap, set_to_local_storage, FocusTrapGuard, Position, Text};
+
+const DRAWER_SIZE_NAME: &str = "wu-drawer-size";
+/// Smallest size a drawer can be resized to, in pixels.
+const MIN_DRAWER_SIZE: f64 = 64.0;
+/// Swipe velocity above which the drawer closes regardless of the swiped distance, in pixels per millisecond.
+const SWIPE_VELOCITY_THRESHOLD: f64 = 0.5;
+
+/// All possible ways a [`Drawer`] can be displayed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DrawerMode {
+	/// The drawer is displayed in a `<dialog>` over the page.
+	#[default]
+	Overlay,
+	/// The drawer is displayed in the layout and pushes the surrounding content aside.
+	///
+	/// # Note
+	/// Place the drawer in the `left_sidebar` or `right_sidebar` slot of a [`Shell`](crate::Shell)
+	/// to have it shrink the main content area.
+	Push,
+}
 
 /// Displays a panel on an arbitrary side of the screen.
 #[component]
@@ -11,10 +35,23 @@ pub fn Drawer(
 	/// Anything other than `Left`, `Right`, `Top` and `Bottom` will be ignored.
 	#[prop(default = Position::Right)]
 	position: Position,
-	/// Signal to open or close the drawer programmatically.
+	/// Signal that opens or closes the drawer.
+	///
+	/// # Note
+	/// Use [`use_query_open`](crate::use_query_open) to bind it to the URL.
 	#[prop(into)]
 	open: Signal<bool>,
+	/// Setter for `open`, set to `false` when the drawer gets closed from the inside or on
+	/// navigation (only in [`DrawerMode::Overlay`]).
+	#[prop(optional, into)]
+	set_open: Option<SignalSetter<bool>>,
+	/// Whether the drawer is displayed over the page or pushes the content aside.
+	#[prop(optional)]
+	mode: DrawerMode,
 	/// Whether the drawer should be on the viewport or in the local container.
+	///
+	/// # Note
+	/// Ignored in [`DrawerMode::Push`].
 	#[prop(default = true)]
 	global: bool,
 	/// Specifies the default 'class' attribute for the drawer.
@@ -23,34 +60,66 @@ pub fn Drawer(
 	/// Specifies the default 'class' attribute for the drawer container.
 	#[prop(optional, into)]
 	container_class: Text,
+	/// Indicates whether the drawer can be swiped closed on touch devices.
+	#[prop(default = true)]
+	swipeable: bool,
+	/// Fraction of the drawer's size that needs to be swiped for the drawer to close.
+	#[prop(default = 0.35)]
+	swipe_threshold: f64,
+	/// Makes the drawer resizable by dragging its inner edge, persisting the size under the given key.
+	#[prop(optional, into)]
+	resize_key: Option<std::borrow::Cow<'static, str>>,
 	/// Children of the component.
 	children: Children,
 ) -> impl IntoView {
 	// vars
 	let location = leptos_router::hooks::use_location();
 	let dialog_ref = NodeRef::<html::Dialog>::new();
-	let is_open = RwSignal::new(false);
+	let container_ref = NodeRef::<html::Div>::new();
+	let resize_handle_ref = NodeRef::<html::Div>::new();
+	let focus_trap = StoredValue::new_local(None::<FocusTrapGuard>);
+	let swipe_offset = RwSignal::new(0f64);
+	let swipe_started_at = StoredValue::new(0f64);
+	let swipe_cancelled = RwSignal::new(false);
+	let is_open = RwSignal::new(open.get_untracked());
+	let close = move || {
+		is_open.set(false);
+		if let Some(set_open) = set_open {
+			set_open.set(false);
+		}
+	};
+	let size = RwSignal::new(resize_key.as_deref().and_then(get_drawer_size_from_local_storage));
+	let is_resizable = resize_key.is_some();
 
 	// logic
-	Effect::new(move |_| {
-		is_open.set(open.get());
-	});
+	Effect::new(move |_| is_open.set(open.get()));
 	Effect::new(move |_| match is_open.get() {
 		true => {
 			if let Some(dialog) = dialog_ref.get() {
+				// trap before showing so that the element which opened the drawer gets restored afterwards
+				if focus_trap.with_value(Option::is_none) {
+					focus_trap.set_value(Some(push_focus_trap(dialog.clone().into())));
+				}
 				_ = dialog.show_modal();
 			}
 		},
 		false => {
 			if let Some(dialog) = dialog_ref.get() {
 				dialog.close();
+				focus_trap.set_value(None);
 			}
 		},
 	});
-	Effect::new(move |_| {
-		location.pathname.track();
-		is_open.set(false);
-	});
+	// close on navigation, but not on the initial location so that deep links can open the drawer
+	Effect::watch(
+		move || location.pathname.get(),
+		move |_, _, _| {
+			if mode == DrawerMode::Overlay && is_open.get_untracked() {
+				close();
+			}
+		},
+		false,
+	);
 
 	// default to Right if no valid position was passed
 	let position = match position {
@@ -89,35 +158,347 @@ pub fn Drawer(
 		_ => unreachable!("cannot happen since we limited the position to the 4 main ones"),
 	};
 
-	view! {
-		<wu-drawer class="contents">
-			<dialog node_ref=dialog_ref class=move || format!("group/drawer {} overflow-hidden cover", global.then_some("overlay-viewport").unwrap_or("overlay"))>
-				<div
-					style="\
-						background-color: var(--wu-dynamic-drawer-bg-color);\
-						border-color: var(--wu-dynamic-drawer-border-color);\
-						padding: var(--wu-dynamic-drawer-padding);\
-					"
-					class=move || format!("overlay max-w-lvw max-h-svh h-dvh transition transition-discrete {position_class} {border_class} {shadow_class} {size_class} {container_class}")
+	#[rustfmt::skip]
+	let (resize_handle_class, touch_action) = match position {
+		Position::Left => ("right-0 inset-y-0 w-1 cursor-ew-resize", "pan-y"),
+		Position::Right => ("left-0 inset-y-0 w-1 cursor-ew-resize", "pan-y"),
+		Position::Top => ("bottom-0 inset-x-0 h-1 cursor-ns-resize", "pan-x"),
+		Position::Bottom => ("top-0 inset-x-0 h-1 cursor-ns-resize", "pan-x"),
+		_ => unreachable!("cannot happen since we limited the position to the 4 main ones"),
+	};
+
+	// swipe to close
+	let is_swiping = match swipeable {
+		false => Signal::stored(false),
+		true => {
+			let UseDraggableReturn { is_dragging, .. } = use_draggable_with_options(
+				container_ref,
+				UseDraggableOptions::default()
+					.pointer_types(vec![PointerType::Touch])
+					// makes the reported position the distance from the start of the swipe
+					.target_offset(|_| (0.0, 0.0))
+					.on_start(move |args| {
+						swipe_started_at.set_value(args.event.time_stamp());
+						swipe_cancelled.set(false);
+						swipe_offset.set(0.0);
+						true
+					})
+					.on_move(move |args| swipe_offset.set(closing_distance(position, args.position)))
+					.on_end(move |args| {
+						// the draggable still ends swipes the browser has taken over on the next pointer release
+						if swipe_cancelled.get_untracked() {
+							return;
+						}
+						// the reported position only changes on moves, so a tap would report the previous swipe
+						let distance = swipe_offset.get_untracked();
+						let elapsed = args.event.time_stamp() - swipe_started_at.get_value();
+						let size = container_ref
+							.get_untracked()
+							.map(|container| {
+								let rect = container.get_bounding_client_rect();
+								match position {
+									Position::Left | Position::Right => rect.width(),
+									_ => rect.height(),
+								}
+							})
+							.unwrap_or(f64::INFINITY);
+						if distance > size * swipe_threshold || distance / elapsed.max(1.0) > SWIPE_VELOCITY_THRESHOLD {
+							close();
+						}
+						swipe_offset.set(0.0);
+					}),
+			);
+			// the browser takes over swipes going along the drawer, which the draggable never ends
+			_ = leptos_use::use_event_listener(container_ref, leptos::ev::pointercancel, move |_| {
+				swipe_cancelled.set(true);
+				swipe_offset.set(0.0);
+			});
+			Signal::derive(move || is_dragging.get() && !swipe_cancelled.get())
+		},
+	};
+
+	// resizing
+	if let Some(resize_key) = resize_key {
+		_ = use_draggable_with_options(
+			resize_handle_ref,
+			UseDraggableOptions::default()
+				.pointer_types(vec![PointerType::Mouse, PointerType::Pen])
+				.prevent_default(true)
+				.on_move(move |args| {
+					let Some(container) = container_ref.get_untracked() else {
+						return;
+					};
+					// the container is always anchored to the side it is on
+					let rect = container.get_bounding_client_rect();
+					let (max_width, max_height) = match dialog_ref.get_untracked() {
+						Some(dialog) => {
+							let bounds = dialog.get_bounding_client_rect();
+							(bounds.width(), bounds.height())
+						},
+						None => (
+							window().inner_width().ok().and_then(|width| width.as_f64()).unwrap_or(f64::INFINITY),
+							window().inner_height().ok().and_then(|height| height.as_f64()).unwrap_or(f64::INFINITY),
+						),
+					};
+					let (x, y) = (args.event.client_x() as f64, args.event.client_y() as f64);
+					let (new_size, max_size) = match position {
+						Position::Left => (x - rect.left(), max_width),
+						Position::Right => (rect.right() - x, max_width),
+						Position::Top => (y - rect.top(), max_height),
+						_ => (rect.bottom() - y, max_height),
+					};
+					size.set(Some(new_size.clamp(MIN_DRAWER_SIZE, max_size.max(MIN_DRAWER_SIZE))));
+				})
+				.on_end(move |_| {
+					if let Some(size) = size.get_untracked() {
+						set_drawer_size_to_local_storage(&resize_key, size);
+					}
+				}),
+		);
+	}
+
+	let mode_class = match (mode, position) {
+		(DrawerMode::Overlay, _) => format!("overlay max-w-lvw max-h-svh h-dvh transition transition-discrete {position_class} {shadow_class}"),
+		(DrawerMode::Push, Position::Left | Position::Right) => "relative h-full".to_string(),
+		(DrawerMode::Push, _) => "relative w-full".to_string(),
+	};
+	let panel = view! {
+		<div
+			node_ref=container_ref
+			style=move || {
+				let size = size.get().map(|size| format!("--wu-dynamic-drawer-size: {size}px;")).unwrap_or_default();
+				let offset = swipe_offset.get();
+				let translate = match position {
+					Position::Left => format!("-{offset}px 0"),
+					Position::Right => format!("{offset}px 0"),
+					Position::Top => format!("0 -{offset}px"),
+					_ => format!("0 {offset}px"),
+				};
+				let transition = if is_swiping.get() { "transition: none;" } else { "" };
+				format!("\
+					background-color: var(--wu-dynamic-drawer-bg-color);\
+					border-color: var(--wu-dynamic-drawer-border-color);\
+					padding: var(--wu-dynamic-drawer-padding);\
+					touch-action: {touch_action};\
+					translate: {translate};\
+					{size}{transition}\
+				")
+			}
+			class=move || format!("{mode_class} {border_class} {size_class} {container_class}")
+		>
+			// Content
+			<div class=move || format!("cover {class}")>
+				{children()}
+			</div>
+			// Resize handle
+			{is_resizable.then(move || view! {
+				<div node_ref=resize_handle_ref class=format!("absolute {resize_handle_class}") />
+			})}
+			// Close button
+			<div
+				style="\
+					margin-top: var(--wu-dynamic-drawer-padding);\
+					margin-right: var(--wu-dynamic-drawer-padding);\
+				"
+				class="internal-tr"
+			>
+				<button on:click=move |_| close() class="btn-icon size-8 autohighlight text-content-sideinfo">
+					<span class="icon i-o-x-mark"/>
+				</button>
+			</div>
+		</div>
+	};
+
+	match mode {
+		DrawerMode::Overlay => Either::Left(view! {
+			<wu-drawer class="contents">
+				<dialog
+					node_ref=dialog_ref
+					on:cancel=move |ev: leptos::ev::Event| {
+						ev.prevent_default();
+						close();
+					}
+					class=move || format!("group/drawer {} overflow-hidden cover", global.then_some("overlay-viewport").unwrap_or("overlay"))
 				>
-					// Content
-					<div class=move || format!("cover {class}")>
-						{children()}
-					</div>
-					// Close button
-					<div
-						style="\
-							margin-top: var(--wu-dynamic-drawer-padding);\
-							margin-right: var(--wu-dynamic-drawer-padding);\
-						"
-						class="internal-tr"
-					>
-						<button on:click=move |_| is_open.set(false) class="btn-icon size-8 autohighlight text-content-sideinfo">
-							<span class="icon i-o-x-mark"/>
-						</button>
-					</div>
-				</div>
-			</dialog>
-		</wu-drawer>
+					{panel}
+				</dialog>
+			</wu-drawer>
+		}),
+		// the wrapper animates its own size while the panel keeps its size, so the content does not reflow
+		DrawerMode::Push => Either::Right(view! {
+			<wu-drawer
+				style=move || {
+					let dimension = match position {
+						Position::Left | Position::Right => "width",
+						_ => "height",
+					};
+					let size = size.get().map(|size| format!("--wu-dynamic-drawer-size: {size}px;")).unwrap_or_default();
+					let value = if is_open.get() { "var(--wu-dynamic-drawer-size)" } else { "0px" };
+					let transition = if is_swiping.get() { "transition: none;" } else { "" };
+					format!("{dimension}: {value}; {size}{transition}")
+				}
+				inert=move || !is_open.get()
+				class="flex-none block overflow-hidden transition-all"
+			>
+				{panel}
+			</wu-drawer>
+		}),
+	}
+}
+
+/// Gets how far the drawer was swiped in its closing direction.
+fn closing_distance(position: Position, delta: PointerPosition) -> f64 {
+	match position {
+		Position::Left => -delta.x,
+		Position::Right => delta.x,
+		Position::Top => -delta.y,
+		_ => delta.y,
+	}
+	.max(0.0)
+}
+
+fn get_drawer_size_from_local_storage(key: &str) -> Option<f64> {
+	let name = format!("{DRAWER_SIZE_NAME}-{key}");
+	match get_from_local_storage(&name)?.parse::<f64>() {
+		Ok(size) => Some(size),
+		Err(err) => {
+			log::error!("could not parse `{name}`: {err}");
+			None
+		},
+	}
+}
+
+fn set_drawer_size_to_local_storage(key: &str, size: f64) {
+	set_to_local_storage(&format!("{DRAWER_SIZE_NAME}-{key}"), &size.to_string());
+}
+
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DrawerMarker<M>(std::marker::PhantomData<M>);
+
+crate::generate_marker_type!(
+	#[doc(hidden)]
+	OpenDrawerMarker
+);
+crate::generate_marker_type!(
+	#[doc(hidden)]
+	CloseDrawerMarker
+);
+
+/// Type alias for a drawer name.
+pub type DrawerName = &'static str;
+/// Opens a named drawer registered in a [`DrawerHook`].
+pub type OpenDrawer<M> = crate::utils::Marked<DrawerMarker<(M, OpenDrawerMarker)>, Callback<DrawerName>>;
+/// Closes a named drawer registered in a [`DrawerHook`].
+pub type CloseDrawer<M> = crate::utils::Marked<DrawerMarker<(M, CloseDrawerMarker)>, Callback<DrawerName>>;
+
+/// A drawer to be registered in a [`DrawerHook`].
+#[derive(Clone)]
+pub struct NamedDrawer {
+	/// Unique name of the drawer.
+	name: DrawerName,
+	/// What side to put the drawer on.
+	position: Position,
+	/// Drawer view.
+	view: ViewFn,
+	/// Query parameter key the drawer is bound to.
+	query_key: Option<&'static str>,
+}
+
+impl NamedDrawer {
+	/// Creates a named drawer from a view.
+	pub fn new(name: DrawerName, position: Position, view_fn: impl Into<ViewFn>) -> Self {
+		Self {
+			name,
+			position,
+			view: view_fn.into(),
+			query_key: None,
+		}
+	}
+
+	/// Binds the drawer to the URL, opening it when the query parameter `key` equals its name.
+	pub fn with_query(mut self, key: &'static str) -> Self {
+		self.query_key = Some(key);
+		self
+	}
+}
+
+/// Provides any number of named drawers to its children.
+///
+/// # Example
+/// ```rust,ignore
+/// <wu::DrawerHook<Main> drawers=vec![wu::NamedDrawer::new("nav", wu::Position::Left, Navigation)]>
+///     ...
+/// </wu::DrawerHook<Main>>
+///
+/// // somewhere deep in the tree
+/// let open_drawer = expect_context::<wu::OpenDrawer<Main>>();
+/// <button on:click=move |_| open_drawer.run("nav")>"Menu"</button>
+/// ```
+#[component]
+pub fn DrawerHook<M>(
+	#[prop(optional)] _phant: std::marker::PhantomData<M>,
+	/// Drawers available to the children.
+	#[prop(into)]
+	drawers: Vec<NamedDrawer>,
+	/// Whether the drawers should be on the viewport or in the local container.
+	#[prop(default = true)]
+	global: bool,
+	/// Specifies the default 'class' attribute for all drawers.
+	#[prop(optional, into)]
+	class: Text,
+	/// Specifies the default 'class' attribute for all drawer containers.
+	#[prop(optional, into)]
+	container_class: Text,
+	/// Children of the component.
+	children: Children,
+) -> impl IntoView
+where
+	M: Send + Sync + 'static,
+{
+	// vars
+	let drawers = drawers
+		.into_iter()
+		.map(|drawer| {
+			let open = match drawer.query_key {
+				Some(key) => crate::utils::use_query_open(key, drawer.name),
+				None => RwSignal::new(false),
+			};
+			(drawer, open)
+		})
+		.collect::<Vec<_>>();
+	let open_signals = StoredValue::new(drawers.iter().map(|(drawer, open)| (drawer.name, *open)).collect::<Vec<_>>());
+	let find_drawer = move |name: DrawerName| {
+		let open = open_signals.with_value(|drawers| drawers.iter().find(|(other, _)| *other == name).map(|(_, open)| *open));
+		if open.is_none() {
+			log::error!("DrawerHook: drawer with name '{name}' does not exist");
+		}
+		open
+	};
+
+	// provide contexts
+	provide_context(OpenDrawer::<M>::new(Callback::new(move |name| {
+		if let Some(open) = find_drawer(name) {
+			open.set(true);
+		}
+	})));
+	provide_context(CloseDrawer::<M>::new(Callback::new(move |name| {
+		if let Some(open) = find_drawer(name) {
+			open.set(false);
+		}
+	})));
+
+	view! {
+		{children()}
+		<wu-drawers class="contents">
+			{drawers
+				.into_iter()
+				.map(|(drawer, open)| view! {
+					<Drawer position=drawer.position open set_open=open global class container_class>
+						{drawer.view.run()}
+					</Drawer>
+				})
+				.collect::<Vec<_>>()}
+		</wu-drawers>
 	}
 }
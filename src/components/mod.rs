@@ -2,6 +2,7 @@ mod drawer;
 mod modal;
 mod shell;
 mod tabs;
+mod tab_groups;
 mod toasts;
 mod stack_context;
 mod debug_console;
@@ -9,10 +10,12 @@ mod input;
 mod dropdown;
 mod button;
 mod table;
+mod dialogs;
 pub use drawer::*;
 pub use modal::*;
 pub use shell::*;
 pub use tabs::*;
+pub use tab_groups::*;
 pub use toasts::*;
 pub use stack_context::*;
 pub use debug_console::*;
@@ -20,3 +23,4 @@ pub use input::*;
 pub use dropdown::*;
 pub use button::*;
 pub use table::*;
+pub use dialogs::*;
@@ -1,8 +1,35 @@
-use leptos::{html, prelude::*};
+use leptos::{context::Provider, html, prelude::*};
 
-use crate::utils::Text;
+use crate::utils::{push_focus_trap, FocusTrapGuard, Text};
+
+/// All possible reasons for a modal closing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CloseReason {
+	/// The close button was clicked.
+	Button,
+	/// The `Escape` key was pressed.
+	Escape,
+	/// The backdrop around the modal was clicked.
+	Backdrop,
+	/// The `open` signal was set to `false` from the outside.
+	Programmatic,
+}
 
 /// A modal that provides an ergonomic wrapper around `<dialog>`.
+///
+/// # Example
+/// ```rust,ignore
+/// let open = RwSignal::new(false);
+/// let has_unsaved_changes = RwSignal::new(false);
+/// <Modal
+///     open
+///     set_open=open
+///     before_close=move |_| !has_unsaved_changes.get_untracked()
+///     on_close=move |reason| log::info!("modal closed: {reason:?}")
+/// >
+///     ...
+/// </Modal>
+/// ```
 #[component]
 pub fn Modal(
 	/// Specifies the default 'class' attribute for the modal.
@@ -11,46 +38,126 @@ pub fn Modal(
 	/// Specifies the default 'class' attribute for the modal container.
 	#[prop(optional, into)]
 	container_class: Text,
-	/// Signal that opens or closes the modal programmatically.
+	/// Signal that opens or closes the modal.
 	#[prop(into)]
 	open: Signal<bool>,
+	/// Setter for `open`, set to `false` when the modal gets closed from the inside.
+	#[prop(optional, into)]
+	set_open: Option<SignalSetter<bool>>,
 	/// Indicates whether the modal is closeable.
 	#[prop(default = true, into)]
 	closeable: bool,
+	/// Indicates whether clicking on the backdrop closes the modal.
+	///
+	/// # Note
+	/// Has no effect if the modal is not closeable.
+	#[prop(default = false, into)]
+	backdrop_closeable: bool,
 	/// Indicates whether the modal is local or global (within the local container or on the viewport).
 	#[prop(default = true, into)]
 	global: bool,
+	/// Logic to run after the modal closes.
+	#[prop(optional, into)]
+	on_close: Option<Callback<CloseReason>>,
+	/// Logic to run before the modal closes from the inside, returning whether closing is allowed.
+	///
+	/// # Note
+	/// Programmatic closes cannot be vetoed.
+	#[prop(optional, into)]
+	before_close: Option<Callback<CloseReason, bool>>,
 	/// Children of the component.
 	children: Children,
 ) -> impl IntoView {
 	// vars
 	let dialog_ref = NodeRef::<html::Dialog>::new();
-	let is_open = RwSignal::new(false);
+	let close_reason = StoredValue::new(None::<CloseReason>);
+	let focus_trap = StoredValue::new_local(None::<FocusTrapGuard>);
+	let is_open = RwSignal::new(open.get_untracked());
+	let request_close = move |reason: CloseReason| {
+		if !closeable {
+			return;
+		}
+		if let Some(before_close) = before_close {
+			if !before_close.run(reason) {
+				return;
+			}
+		}
+		close_reason.set_value(Some(reason));
+		is_open.set(false);
+		if let Some(set_open) = set_open {
+			set_open.set(false);
+		}
+	};
 
 	// logic
-	Effect::new(move |_| {
-		is_open.set(open.get());
-	});
-	Effect::new(move |_| match is_open.get() {
-		true => {
-			if let Some(dialog) = dialog_ref.get() {
-				if closeable {
-					_ = dialog.show_modal();
-				} else {
-					_ = dialog.show();
-				}
+	Effect::new(move |_| is_open.set(open.get()));
+	Effect::new(move |was_open: Option<bool>| {
+		let is_open = is_open.get();
+		if let Some(dialog) = dialog_ref.get() {
+			match is_open {
+				true => {
+					// trap before showing so that the element which opened the modal gets restored afterwards
+					if focus_trap.with_value(Option::is_none) {
+						focus_trap.set_value(Some(push_focus_trap(dialog.clone().into())));
+					}
+					if closeable {
+						_ = dialog.show_modal();
+					} else {
+						_ = dialog.show();
+					}
+				},
+				false => {
+					dialog.close();
+					focus_trap.set_value(None);
+				},
 			}
-		},
-		false => {
-			if let Some(dialog) = dialog_ref.get() {
-				dialog.close();
+		}
+
+		// notify only on actual transitions
+		if was_open == Some(true) && !is_open {
+			let reason = close_reason.write_value().take().unwrap_or(CloseReason::Programmatic);
+			if let Some(on_close) = on_close {
+				untrack(move || on_close.run(reason));
 			}
-		},
+		}
+
+		is_open
 	});
 
 	view! {
 		<wu-modal class="contents">
-			<dialog node_ref=dialog_ref class=format!("group/modal {} cover z-9999", global.then_some("overlay-viewport").unwrap_or("overlay"))>
+			<dialog
+				node_ref=dialog_ref
+				on:cancel=move |ev: leptos::ev::Event| {
+					// browsers allow cancelling only once per user activation, the rest is handled on close
+					if ev.cancelable() {
+						ev.prevent_default();
+						request_close(CloseReason::Escape);
+					}
+				}
+				on:close=move |_| {
+					// the dialog got closed natively, so sync the state or reopen it if closing is not allowed
+					if !is_open.get_untracked() {
+						return;
+					}
+					request_close(CloseReason::Escape);
+					if is_open.get_untracked() {
+						if let Some(dialog) = dialog_ref.get_untracked() {
+							match closeable {
+								true => _ = dialog.show_modal(),
+								false => dialog.show(),
+							}
+						}
+					}
+				}
+				on:click=move |ev| {
+					// only clicks directly on the dialog are outside of the container
+					if backdrop_closeable && ev.target() == ev.current_target() {
+						request_close(CloseReason::Backdrop);
+					}
+				}
+				class=format!("group/modal {} cover z-9999", global.then_some("overlay-viewport").unwrap_or("overlay"))
+			>
 				<div
 					style="\
 						background-color: var(--wu-dynamic-modal-bg-color);\
@@ -75,7 +182,7 @@ pub fn Modal(
 							"
 							class="internal-tr"
 						>
-							<button on:click=move |_| is_open.set(false) class="btn-icon size-8 autohighlight text-content-sideinfo">
+							<button on:click=move |_| request_close(CloseReason::Button) class="btn-icon size-8 autohighlight text-content-sideinfo">
 								<span class="icon i-o-x-mark"/>
 							</button>
 						</div>
@@ -85,3 +192,138 @@ pub fn Modal(
 		</wu-modal>
 	}
 }
+
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModalMarker<M>(std::marker::PhantomData<M>);
+
+crate::generate_marker_type!(
+	#[doc(hidden)]
+	AddModalMarker
+);
+crate::generate_marker_type!(
+	#[doc(hidden)]
+	CloseModalMarker
+);
+
+pub type ModalId = u64;
+/// Pushes a new modal onto the modal stack.
+pub type AddModal<M> = crate::utils::Marked<ModalMarker<(M, AddModalMarker)>, Callback<ViewFn, ModalHandle>>;
+/// Closes the top-most modal on the modal stack.
+pub type CloseModal<M> = crate::utils::Marked<ModalMarker<(M, CloseModalMarker)>, Callback<()>>;
+
+/// A type that allows closing a modal pushed by [`AddModal`].
+///
+/// # Note
+/// The handle is also provided as a context to the modal's view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModalHandle {
+	modals_ref: RwSignal<Vec<ModalWithId>>,
+	modal_id: ModalId,
+}
+
+impl ModalHandle {
+	/// Gets the ID of the modal.
+	pub fn id(&self) -> ModalId {
+		self.modal_id
+	}
+
+	/// Checks whether the modal is still on the stack.
+	///
+	/// # Note
+	/// Call in a signal for reactivity.
+	pub fn is_open(&self) -> bool {
+		self.modals_ref.with(|modals| modals.iter().any(|modal| modal.id == self.modal_id))
+	}
+
+	/// Closes the modal.
+	pub fn close(&self) {
+		self.modals_ref.write().retain(|modal: &ModalWithId| modal.id != self.modal_id);
+	}
+}
+
+/// Imperative modals utilizing a stack system.
+///
+/// Modals pushed later are always displayed above the ones pushed earlier.
+///
+/// # Example
+/// ```rust,ignore
+/// let add_modal = expect_context::<wu::AddModal<Main>>();
+/// let handle = add_modal.run(ViewFn::from(move || view! {
+///     <p>"Hello from a modal"</p>
+///     <button on:click=move |_| expect_context::<wu::ModalHandle>().close()>"Ok"</button>
+/// }));
+/// ```
+#[component]
+pub fn ModalHook<M>(
+	#[prop(optional)] _phant: std::marker::PhantomData<M>,
+	/// Specifies the default 'class' attribute for all modals.
+	#[prop(optional, into)]
+	class: Text,
+	/// Specifies the default 'class' attribute for all modal containers.
+	#[prop(optional, into)]
+	container_class: Text,
+	/// Indicates whether the modals are closeable.
+	#[prop(default = true, into)]
+	closeable: bool,
+	/// Children of the component.
+	children: Children,
+) -> impl IntoView
+where
+	M: Send + Sync + 'static,
+{
+	// modal creation
+	let modal_id = RwSignal::new(0 as ModalId);
+	let modals = RwSignal::new(Vec::<ModalWithId>::default());
+	provide_context(AddModal::<M>::new(Callback::new(move |view: ViewFn| {
+		let id = modal_id.get_untracked();
+		modals.update(move |modals| modals.push(ModalWithId { id, view }));
+		modal_id.update(|n| *n = n.overflowing_add(1).0);
+		ModalHandle { modals_ref: modals, modal_id: id }
+	})));
+	provide_context(CloseModal::<M>::new(Callback::new(move |_| {
+		modals.update(move |modals| _ = modals.pop());
+	})));
+
+	view! {
+		{children()}
+		<wu-modals class="contents">
+			<For
+				each=move || modals.get()
+				key=move |modal| modal.id
+				children=move |modal| {
+					let modal_handle = ModalHandle {
+						modals_ref: modals,
+						modal_id: modal.id,
+					};
+
+					view! {
+						<Modal
+							open=RwSignal::new(true)
+							closeable
+							class
+							container_class
+							on_close=move |_| modal_handle.close()
+						>
+							<Provider value=modal_handle>
+								{modal.view.run()}
+							</Provider>
+						</Modal>
+					}
+				}
+			/>
+		</wu-modals>
+	}
+}
+
+#[derive(Clone)]
+struct ModalWithId {
+	id: ModalId,
+	view: ViewFn,
+}
+
+impl PartialEq for ModalWithId {
+	fn eq(&self, other: &Self) -> bool {
+		self.id == other.id
+	}
+}
@@ -0,0 +1,326 @@
+use leptos::prelude::*;
+
+use crate::{
+	components::{TabId, TabSignal, Tabs, TabsController},
+	utils::Text,
+};
+
+pub type TabGroupId = u64;
+
+/// A handle for controlling [`TabGroups`], provided as a context by the component.
+///
+/// All groups share one tab id space, so a tab keeps its id when it moves between groups.
+///
+/// # Example
+/// ```rust,ignore
+/// let groups = TabGroupsController::<MyApp, Document>::new([Document::default()]);
+/// // open the active document side by side
+/// if let Some(id) = groups.group(0).and_then(|group| group.active_id()) {
+///     groups.split(id);
+/// }
+/// ```
+pub struct TabGroupsController<M, T>
+where
+	T: Send + Sync + 'static,
+{
+	groups: RwSignal<Vec<(TabGroupId, TabsController<M, T>)>>,
+	next_group_id: RwSignal<TabGroupId>,
+	next_tab_id: RwSignal<TabId>,
+	/// Group state must outlive whichever component created the group.
+	owner: StoredValue<Owner>,
+	/// Owners of each group's state, freed once the group gets removed.
+	group_owners: StoredValue<Vec<(TabGroupId, Owner)>>,
+}
+
+impl<M, T> Clone for TabGroupsController<M, T>
+where
+	T: Send + Sync + 'static,
+{
+	fn clone(&self) -> Self {
+		*self
+	}
+}
+
+impl<M, T> Copy for TabGroupsController<M, T> where T: Send + Sync + 'static {}
+
+impl<M, T> TabGroupsController<M, T>
+where
+	M: Send + Sync + 'static,
+	T: Send + Sync + 'static,
+{
+	/// Creates a controller with a single group containing the given tabs.
+	pub fn new(tabs: impl IntoIterator<Item = T>) -> Self {
+		let controller = Self {
+			groups: RwSignal::new(Vec::default()),
+			next_group_id: RwSignal::new(0),
+			next_tab_id: RwSignal::new(0),
+			owner: StoredValue::new(Owner::current().expect("should be called inside a component")),
+			group_owners: StoredValue::new(Vec::default()),
+		};
+		let group_id = controller.add_group();
+		if let Some(group) = controller.group_untracked(group_id) {
+			for tab in tabs {
+				group.add(tab);
+			}
+		}
+		controller
+	}
+
+	/// Adds an empty group at the end, returning its id.
+	pub fn add_group(&self) -> TabGroupId {
+		self.insert_group(usize::MAX)
+	}
+
+	/// Moves the tab to the given index of the given group.
+	///
+	/// Groups left empty by the move get removed, unless it is the last group.
+	pub fn move_tab(&self, tab_id: TabId, to_group_id: TabGroupId, idx: usize) {
+		let Some(from_group_id) = self.group_of_untracked(tab_id) else {
+			log::error!("TabGroupsController::move_tab: tab with id '{tab_id}' does not exist");
+			return;
+		};
+		let Some(to_group) = self.group_untracked(to_group_id) else {
+			log::error!("TabGroupsController::move_tab: group with id '{to_group_id}' does not exist");
+			return;
+		};
+		if from_group_id == to_group_id {
+			to_group.move_tab(tab_id, idx);
+			return;
+		}
+
+		let Some(from_group) = self.group_untracked(from_group_id) else {
+			return;
+		};
+		for (_, tab) in from_group.detach(&[tab_id]) {
+			to_group.insert(tab, idx);
+		}
+		self.remove_group_if_empty(from_group_id);
+	}
+
+	/// Moves the tab into a new group placed right after its current group, returning the new group's id.
+	pub fn split(&self, tab_id: TabId) -> Option<TabGroupId> {
+		let Some(from_group_id) = self.group_of_untracked(tab_id) else {
+			log::error!("TabGroupsController::split: tab with id '{tab_id}' does not exist");
+			return None;
+		};
+		let idx = self.groups.with_untracked(|groups| groups.iter().position(|(id, _)| *id == from_group_id))?;
+		let group_id = self.insert_group(idx + 1);
+		self.move_tab(tab_id, group_id, 0);
+		Some(group_id)
+	}
+
+	/// Moves all tabs of one group to the end of another and removes the emptied group.
+	pub fn merge(&self, from_group_id: TabGroupId, into_group_id: TabGroupId) {
+		if from_group_id == into_group_id {
+			return;
+		}
+		let (Some(from_group), Some(into_group)) = (self.group_untracked(from_group_id), self.group_untracked(into_group_id)) else {
+			log::error!("TabGroupsController::merge: group with id '{from_group_id}' or '{into_group_id}' does not exist");
+			return;
+		};
+		let active_id = from_group.active_id.get_untracked();
+		let ids = from_group.ids_untracked();
+		for (_, tab) in from_group.detach(&ids) {
+			into_group.insert(tab, usize::MAX);
+		}
+		// keep the merged group's active tab in focus
+		if let Some(active_id) = active_id {
+			into_group.set_active(active_id);
+		}
+		self.remove_group_if_empty(from_group_id);
+	}
+
+	/// Gets the ids of all groups, in order.
+	pub fn groups(&self) -> Vec<TabGroupId> {
+		self.groups.with(|groups| groups.iter().map(|(id, _)| *id).collect())
+	}
+
+	/// Gets the controller of the group with the given id.
+	pub fn group(&self, group_id: TabGroupId) -> Option<TabsController<M, T>> {
+		self.groups.with(|groups| groups.iter().find(|(id, _)| *id == group_id).map(|(_, group)| *group))
+	}
+
+	/// Gets the id of the group containing the tab.
+	pub fn group_of(&self, tab_id: TabId) -> Option<TabGroupId> {
+		self.groups.with(|groups| groups.iter().find(|(_, group)| group.ids().contains(&tab_id)).map(|(id, _)| *id))
+	}
+
+	/// Gets the current layout of all groups, which can be serialized and restored later.
+	pub fn layout(&self) -> TabGroupsLayout<T>
+	where
+		T: Clone,
+	{
+		let groups = self.groups.with(|groups| {
+			groups
+				.iter()
+				.map(|(_, group)| {
+					let tabs = group.tabs();
+					let active_id = group.active_id();
+					TabGroupLayout {
+						active: active_id.and_then(|active_id| tabs.iter().position(|tab| tab.with(|tab| tab.id == active_id))),
+						tabs: tabs.iter().map(|tab| tab.with(|tab| T::clone(tab))).collect(),
+					}
+				})
+				.collect()
+		});
+		TabGroupsLayout { groups }
+	}
+
+	/// Replaces all groups with a previously saved layout.
+	pub fn restore(&self, layout: TabGroupsLayout<T>) {
+		for (group_id, group) in self.groups.try_update(std::mem::take).unwrap_or_default() {
+			self.dispose_group(group_id, group);
+		}
+		for group_layout in layout.groups {
+			let group_id = self.add_group();
+			if let Some(group) = self.group_untracked(group_id) {
+				group.restore(group_layout.tabs, group_layout.active);
+			}
+		}
+		// there is always at least one group to add tabs to
+		if self.groups.with_untracked(Vec::is_empty) {
+			self.add_group();
+		}
+	}
+
+	pub(crate) fn group_with_controller(&self, controller: TabsController<M, T>) -> Option<TabGroupId> {
+		self.groups.with_untracked(|groups| groups.iter().find(|(_, group)| *group == controller).map(|(id, _)| *id))
+	}
+
+	fn group_untracked(&self, group_id: TabGroupId) -> Option<TabsController<M, T>> {
+		self.groups.with_untracked(|groups| groups.iter().find(|(id, _)| *id == group_id).map(|(_, group)| *group))
+	}
+
+	fn group_of_untracked(&self, tab_id: TabId) -> Option<TabGroupId> {
+		self.groups.with_untracked(|groups| groups.iter().find(|(_, group)| group.contains(tab_id)).map(|(id, _)| *id))
+	}
+
+	fn insert_group(&self, idx: usize) -> TabGroupId {
+		let id = self.next_group_id.get_untracked();
+		self.next_group_id.set(id + 1);
+		let next_tab_id = self.next_tab_id;
+		// tabs move between groups, so they belong to the controller instead of the group
+		let tab_owner = self.owner.get_value();
+		let group_owner = tab_owner.child();
+		let group = group_owner.with(move || TabsController::with_next_id(next_tab_id, tab_owner, []));
+		self.group_owners.update_value(|group_owners| group_owners.push((id, group_owner)));
+		self.groups.update(move |groups| groups.insert(idx.min(groups.len()), (id, group)));
+		id
+	}
+
+	fn remove_group_if_empty(&self, group_id: TabGroupId) {
+		let mut removed = None;
+		self.groups.update(|groups| {
+			if groups.len() <= 1 {
+				return;
+			}
+			if let Some(idx) = groups.iter().position(|(id, group)| *id == group_id && group.tabs.with_untracked(Vec::is_empty)) {
+				removed = Some(groups.remove(idx));
+			}
+		});
+		if let Some((group_id, group)) = removed {
+			self.dispose_group(group_id, group);
+		}
+	}
+
+	/// Frees a group which has been removed from the list.
+	fn dispose_group(&self, group_id: TabGroupId, group: TabsController<M, T>) {
+		group.clear();
+		let group_owner = self.group_owners.try_update_value(|group_owners| {
+			let idx = group_owners.iter().position(|(id, _)| *id == group_id)?;
+			Some(group_owners.remove(idx).1)
+		});
+		// the group's view still reads its state until it gets removed on the next render
+		if let Some(group_owner) = group_owner.flatten() {
+			request_animation_frame(move || group_owner.cleanup());
+		}
+	}
+}
+
+/// A saveable layout of [`TabGroups`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct TabGroupsLayout<T> {
+	/// All groups, in order.
+	pub groups: Vec<TabGroupLayout<T>>,
+}
+
+/// A saveable layout of a single group of [`TabGroups`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct TabGroupLayout<T> {
+	/// The group's tabs, in order.
+	pub tabs: Vec<T>,
+	/// Index of the active tab.
+	pub active: Option<usize>,
+}
+
+/// Multiple [`Tabs`] side by side, with tabs movable between them.
+///
+/// Tabs can be dragged from one group's tab strip into another's. Provides a
+/// [`TabGroupsController<M, T>`] context, while each group provides its own tab contexts.
+#[component]
+pub fn TabGroups<M, T>(
+	#[prop(optional)] _phant: std::marker::PhantomData<(M, T)>,
+	/// Controller for the groups, used for controlling them from outside of the component.
+	///
+	/// The component creates its own if not provided.
+	#[prop(optional, into)]
+	controller: Option<TabGroupsController<M, T>>,
+	/// Default list of tab contexts of the first group.
+	///
+	/// Ignored if `controller` is provided.
+	#[prop(default = Vec::default(), into)]
+	tabs: Vec<T>,
+	/// Corresponds to the 'class' attribute of elements.
+	#[prop(optional, into)]
+	class: Text,
+	/// Group class.
+	#[prop(optional, into)]
+	group_class: Text,
+	/// List class of every group.
+	#[prop(optional, into)]
+	list_class: Text,
+	/// Can tabs be closed with the Delete key?
+	#[prop(optional)]
+	closeable: bool,
+	/// List item slot.
+	#[prop(into)]
+	item: Callback<TabSignal<T>, AnyView>,
+	/// Content slot.
+	#[prop(into)]
+	content: Callback<TabSignal<T>, AnyView>,
+	/// List fallback.
+	#[prop(optional, into)]
+	list_fallback: ViewFn,
+	/// Content fallback.
+	#[prop(optional, into)]
+	content_fallback: ViewFn,
+) -> impl IntoView
+where
+	M: Send + Sync + 'static,
+	T: Send + Sync + 'static,
+{
+	let controller = controller.unwrap_or_else(|| TabGroupsController::new(tabs));
+	provide_context(controller);
+
+	view! {
+		<wu-tab-groups class=move || format!("horizontal {class}")>
+			<For
+				each=move || controller.groups.get()
+				key=move |(id, _)| *id
+				let:group
+			>
+				<Tabs<M, T>
+					controller=group.1
+					class=group_class
+					list_class=list_class
+					closeable=closeable
+					reorderable=true
+					item=item
+					content=content
+					list_fallback=list_fallback.clone()
+					content_fallback=content_fallback.clone()
+				/>
+			</For>
+		</wu-tab-groups>
+	}
+}
@@ -4,22 +4,56 @@ use crate::utils::*;
 
 /// A table component displaying paginated records and providing a way to view into
 /// windows of records via a footer control part.
+///
+/// The header and rows are generated from `columns`, unless the `header` or `row` slots are
+/// provided, in which case those take precedence.
+///
+/// Columns become sortable by using [`SortableHeader`]s in the header. Clicking a header
+/// cycles its column through ascending, descending and no sorting, while shift-clicking
+/// adds the column to the existing sort.
+///
+/// # Example
+/// ```rust,ignore
+/// <Table
+///     data_source=move |offset, limit, sort| fetch_users(offset, limit, sort)
+///     limit=20
+///     columns=vec![
+///         Column::new("name", "Name", |user: User| user.name).sortable(),
+///         Column::new("email", "Email", |user: User| user.email).hideable(),
+///         Column::new("age", "Age", |user: User| user.age).width("6rem").align(ColumnAlign::End).sortable(),
+///     ]
+/// />
+/// ```
 #[component]
 pub fn Table<T, E, Fut, F>(
 	/// Source for getting the data.
 	///
 	/// # Note
-	/// The parameters of the function returning the future are: (u32, u32)
+	/// The parameters of the function returning the future are: (u64, u64, SortSpec)
 	/// 0: Offset
 	/// 1: Limit
+	/// 2: Requested sort order
 	data_source: F,
 	/// The limit on the number of records to fetch.
 	limit: u64,
+	/// Sort order of the records.
+	#[prop(optional, into)]
+	sort: RwSignal<SortSpec>,
+	/// Columns to generate the header and rows from.
+	#[prop(optional, into)]
+	columns: Vec<Column<T>>,
+	/// Ids of hidden columns.
+	#[prop(optional, into)]
+	hidden_columns: RwSignal<Vec<std::borrow::Cow<'static, str>>>,
 	/// Table header view.
-	#[prop(into)]
-	header: ViewFn,
+	///
+	/// Overrides the header generated from `columns`.
+	#[prop(optional, into)]
+	header: Option<ViewFn>,
 	/// Table row view.
-	#[prop(into)]
+	///
+	/// Overrides the rows generated from `columns`.
+	#[prop(optional, into)]
 	row: LocatableViewFnWithArgs<T>,
 	/// Fallback (loading) view.
 	#[prop(optional, into)]
@@ -41,105 +75,468 @@ where
 	T: Clone + Send + Sync + 'static,
 	E: std::fmt::Debug + Clone + Send + Sync + 'static,
 	Fut: std::future::Future<Output = Result<(u64, Vec<T>), E>> + 'static,
-	F: Fn(u64, u64) -> Fut + 'static,
+	F: Fn(u64, u64, SortSpec) -> Fut + 'static,
 {
 	// vars
 	let offset: RwSignal<u64> = RwSignal::new(0);
-	let data_resource = LocalResource::new(move || data_source(offset.get(), limit));
-
-	move || match data_resource.get() {
-		None => Either::Left(fallback.run()),
-		Some(res) => Either::Right(view! {
-			<div class=move || format!("wtable {class}") style=move || style.get().into_owned()>
-				<table>
-					<thead>
-						{let header = header.clone(); move || header.run()}
-					</thead>
-					{match res {
-						Err(err) => Either::Left(view! {
-							<tbody class=move || tbody_class.get()>
-								<div class="cover flex hvcenter">
-									<div class="vertical gap-2">
-										<div class="flex hcenter">
-											<span class="icon i-o-exclamation-triangle icon-error-500 size-12"/>
+	let data_resource = LocalResource::new(move || data_source(offset.get(), limit, sort.get()));
+	let has_hideable_columns = columns.iter().any(|column| column.hideable);
+	let columns = StoredValue::new(columns);
+	let visible_columns = move || {
+		columns.with_value(|columns| {
+			hidden_columns.with(|hidden_columns| columns.iter().filter(|column| !hidden_columns.contains(&column.id)).cloned().collect::<Vec<_>>())
+		})
+	};
+
+	// logic
+	provide_context(TableSort { sort, offset });
+	// a different order makes the current page meaningless, headers reset it along with
+	// the sort themselves so this only catches sorts changed from outside
+	Effect::watch(
+		move || sort.track(),
+		move |_, _, _| {
+			if offset.get_untracked() != 0 {
+				offset.set(0);
+			}
+		},
+		false,
+	);
+
+	// the menu stays open while the records change
+	let column_menu = has_hideable_columns.then(move || {
+		let menu = AnchoredPopover::new("wu-table-columns");
+		view! {
+			<div class="flex justify-end">
+				<button
+					popovertarget=menu.id.clone()
+					aria-label="Columns"
+					style=menu.anchor_style.clone()
+					class="btn-icon autohighlight size-8"
+				>
+					<span class="icon i-o-view-columns size-4" />
+				</button>
+				<ul
+					id=menu.id.clone()
+					popover
+					style=menu.popover_style.clone()
+					class="vertical gap-1 m-0 p-2 border bg-surface-1 border-surface-2 shadow-lg rounded-md"
+				>
+					{columns.with_value(|columns| {
+						columns
+							.iter()
+							.filter(|column| column.hideable)
+							.map(|column| {
+								let id = StoredValue::new(column.id.clone());
+								view! {
+									<li>
+										<label class="horizontal vcenter gap-2 p-1 rounded-md autohighlight">
+											<input
+												type="checkbox"
+												prop:checked=move || hidden_columns.with(|hidden_columns| id.with_value(|id| !hidden_columns.contains(id)))
+												on:change=move |_| hidden_columns.update(move |hidden_columns| {
+													let id = id.get_value();
+													match hidden_columns.iter().position(|hidden_id| *hidden_id == id) {
+														Some(idx) => _ = hidden_columns.remove(idx),
+														None => hidden_columns.push(id),
+													}
+												})
+											/>
+											{column.title}
+										</label>
+									</li>
+								}
+							})
+							.collect_view()
+					})}
+				</ul>
+			</div>
+		}
+	});
+
+	view! {
+		<div class=move || format!("wtable {class}") style=move || style.get().into_owned()>
+			{column_menu}
+			{move || match data_resource.get() {
+				None => Either::Left(fallback.run()),
+				Some(res) => Either::Right(view! {
+					<table>
+						<thead>
+							{
+								let header = header.clone();
+								move || match header.clone() {
+									Some(header) => Either::Left(header.run()),
+									None => Either::Right(view! {
+										<tr>
+											{move || visible_columns().iter().map(Column::header_view).collect_view()}
+										</tr>
+									}),
+								}
+							}
+						</thead>
+						{match res {
+							Err(err) => Either::Left(view! {
+								<tbody class=move || tbody_class.get()>
+									<div class="cover flex hvcenter">
+										<div class="vertical gap-2">
+											<div class="flex hcenter">
+												<span class="icon i-o-exclamation-triangle icon-error-500 size-12"/>
+											</div>
+											<span class="font-semibold text-content-emph">
+												{format!("{err:?}")}
+											</span>
 										</div>
-										<span class="font-semibold text-content-emph">
-											{format!("{err:?}")}
-										</span>
 									</div>
-								</div>
-							</tbody>
-						}),
-						Ok((total_count, records)) => Either::Right(match total_count == 0 {
-							true => Either::Left(view! {
-								<tbody class=move || tbody_class.get()>
-									{let on_empty = on_empty.clone(); move || on_empty.run()}
 								</tbody>
 							}),
-							false => Either::Right(view! {
-								<tbody class=move || tbody_class.get()>
-									{
-										let row = row.clone();
-										records
-											.into_iter()
-											.map(move |record| row.clone().run(record))
-											.collect::<Vec<_>>()
-									}
-								</tbody>
-								<tfoot>
-									<td class="grow hvcenter">
-										<div class="horizontal gap-4">
-											// Previous
-											<div class="flex hvcenter">
-												{move || match offset.get() != 0 {
-													false => Either::Left(view! { <div class="flex-none size-8"/> }),
-													true => Either::Right(view ! {
-														<button
-															on:click=move |_| offset.update(move |offset| *offset = offset.saturating_sub(1))
-															class="btn-icon autohighlight size-8"
-														>
-															<span class="icon i-o-arrow-left size-4" />
-														</button>
-													}),
-												}}
-											</div>
-											// Current pages
-											<div class="flex vcenter">
-												<span class="text-lg">
-													{move || offset.get() + 1}
-													" / "
-													{
-														if limit != 0 {
-															(total_count + limit - 1) / limit
-														} else {
-															1
-														}
-
-													}
-												</span>
-											</div>
-											// Next
-											<div class="flex hvcenter">
-												{move || match limit != 0 && offset.get() < (total_count + limit - 1) / limit - 1 {
-													false => Either::Left(view! { <div class="flex-none size-8"/> }),
+							Ok((total_count, records)) => Either::Right(match total_count == 0 {
+								true => Either::Left(view! {
+									<tbody class=move || tbody_class.get()>
+										{let on_empty = on_empty.clone(); move || on_empty.run()}
+									</tbody>
+								}),
+								false => Either::Right(view! {
+									<tbody class=move || tbody_class.get()>
+										{
+											let row = row.clone();
+											records
+												.into_iter()
+												.map(move |record| match row.is_default {
+													false => Either::Left(row.run(record)),
 													true => Either::Right(view! {
-														<button
-															on:click=move |_| offset.update(move |offset| *offset = offset.saturating_add(1))
-															class="btn-icon autohighlight size-8"
-														>
-															<span class="icon i-o-arrow-right size-4" />
-														</button>
+														<tr>
+															{move || visible_columns().iter().map(|column| column.cell_view(record.clone())).collect_view()}
+														</tr>
 													}),
-												}}
+												})
+												.collect::<Vec<_>>()
+										}
+									</tbody>
+									<tfoot>
+										<td class="grow hvcenter">
+											<div class="horizontal gap-4">
+												// Previous
+												<div class="flex hvcenter">
+													{move || match offset.get() != 0 {
+														false => Either::Left(view! { <div class="flex-none size-8"/> }),
+														true => Either::Right(view ! {
+															<button
+																on:click=move |_| offset.update(move |offset| *offset = offset.saturating_sub(1))
+																class="btn-icon autohighlight size-8"
+															>
+																<span class="icon i-o-arrow-left size-4" />
+															</button>
+														}),
+													}}
+												</div>
+												// Current pages
+												<div class="flex vcenter">
+													<span class="text-lg">
+														{move || offset.get() + 1}
+														" / "
+														{
+															if limit != 0 {
+																(total_count + limit - 1) / limit
+															} else {
+																1
+															}
+
+														}
+													</span>
+												</div>
+												// Next
+												<div class="flex hvcenter">
+													{move || match limit != 0 && offset.get() < (total_count + limit - 1) / limit - 1 {
+														false => Either::Left(view! { <div class="flex-none size-8"/> }),
+														true => Either::Right(view! {
+															<button
+																on:click=move |_| offset.update(move |offset| *offset = offset.saturating_add(1))
+																class="btn-icon autohighlight size-8"
+															>
+																<span class="icon i-o-arrow-right size-4" />
+															</button>
+														}),
+													}}
+												</div>
 											</div>
-										</div>
-									</td>
-								</tfoot>
+										</td>
+									</tfoot>
+								}),
 							}),
-						}),
-					}}
-				</table>
-			</div>
-		}),
+						}}
+					</table>
+				}),
+			}}
+		</div>
+	}
+}
+
+/// Horizontal alignment of a [`Column`]'s cells.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ColumnAlign {
+	#[default]
+	Start,
+	Center,
+	End,
+}
+
+impl ColumnAlign {
+	fn class(self) -> &'static str {
+		match self {
+			Self::Start => "text-start",
+			Self::Center => "text-center",
+			Self::End => "text-end",
+		}
+	}
+}
+
+/// A typed column definition of a [`Table`].
+pub struct Column<T>
+where
+	T: Send + Sync + 'static,
+{
+	id: std::borrow::Cow<'static, str>,
+	title: Text,
+	cell: LocatableViewFnWithArgs<T>,
+	width: Option<std::borrow::Cow<'static, str>>,
+	align: ColumnAlign,
+	sortable: bool,
+	hideable: bool,
+}
+
+impl<T> Column<T>
+where
+	T: Send + Sync + 'static,
+{
+	/// Creates a column with the given id, header title and cell renderer.
+	///
+	/// The id is what the data source gets in the [`SortSpec`].
+	#[track_caller]
+	pub fn new(id: impl Into<std::borrow::Cow<'static, str>>, title: impl Into<Text>, cell: impl Into<LocatableViewFnWithArgs<T>>) -> Self {
+		Self {
+			id: id.into(),
+			title: title.into(),
+			cell: cell.into(),
+			width: None,
+			align: ColumnAlign::default(),
+			sortable: false,
+			hideable: false,
+		}
+	}
+
+	/// Sets the width of the column as a CSS length.
+	pub fn width(mut self, width: impl Into<std::borrow::Cow<'static, str>>) -> Self {
+		self.width = Some(width.into());
+		self
+	}
+
+	/// Sets the alignment of the column's cells.
+	pub fn align(mut self, align: ColumnAlign) -> Self {
+		self.align = align;
+		self
+	}
+
+	/// Makes the column sortable through its header.
+	pub fn sortable(mut self) -> Self {
+		self.sortable = true;
+		self
+	}
+
+	/// Makes the column hideable through the table's column menu.
+	pub fn hideable(mut self) -> Self {
+		self.hideable = true;
+		self
+	}
+
+	/// Gets the column id.
+	pub fn id(&self) -> &str {
+		&self.id
+	}
+
+	fn style(&self) -> String {
+		self.width.as_ref().map(|width| format!("width: {width};")).unwrap_or_default()
+	}
+
+	fn header_view(&self) -> AnyView {
+		let title = self.title;
+		match self.sortable {
+			true => view! {
+				<SortableHeader column=self.id.clone() class=self.align.class() style=self.style()>
+					{title}
+				</SortableHeader>
+			}
+			.into_any(),
+			false => view! { <th class=self.align.class() style=self.style()>{title}</th> }.into_any(),
+		}
+	}
+
+	fn cell_view(&self, record: T) -> impl IntoView {
+		view! { <td class=self.align.class()>{self.cell.run(record)}</td> }
+	}
+}
+
+impl<T> Clone for Column<T>
+where
+	T: Send + Sync + 'static,
+{
+	fn clone(&self) -> Self {
+		Self {
+			id: self.id.clone(),
+			title: self.title,
+			cell: self.cell.clone(),
+			width: self.width.clone(),
+			align: self.align,
+			sortable: self.sortable,
+			hideable: self.hideable,
+		}
+	}
+}
+
+impl<T> std::fmt::Debug for Column<T>
+where
+	T: Send + Sync + 'static,
+{
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("Column").field("id", &self.id).field("title", &self.title).finish_non_exhaustive()
+	}
+}
+
+/// Direction in which a column is sorted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum SortDirection {
+	Ascending,
+	Descending,
+}
+
+/// A column and the direction it is sorted in.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct SortKey {
+	/// Column id.
+	pub column: std::borrow::Cow<'static, str>,
+	/// Sort direction.
+	pub direction: SortDirection,
+}
+
+/// Requested sort order of a [`Table`], from the most to the least significant column.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct SortSpec {
+	keys: Vec<SortKey>,
+}
+
+impl SortSpec {
+	/// Creates a sort order from the given keys.
+	pub fn new(keys: impl IntoIterator<Item = SortKey>) -> Self {
+		Self { keys: keys.into_iter().collect() }
+	}
+
+	/// Gets the keys, from the most to the least significant.
+	pub fn keys(&self) -> &[SortKey] {
+		&self.keys
+	}
+
+	/// Is no sorting requested?
+	pub fn is_empty(&self) -> bool {
+		self.keys.is_empty()
+	}
+
+	/// Gets the direction the column is sorted in and its position among the keys.
+	pub fn get(&self, column: &str) -> Option<(usize, SortDirection)> {
+		self.keys.iter().position(|key| key.column == column).map(|idx| (idx, self.keys[idx].direction))
+	}
+
+	/// Cycles the column through ascending, descending and no sorting.
+	///
+	/// Unless `multi` is set, all other columns stop being sorted.
+	fn cycle(&mut self, column: std::borrow::Cow<'static, str>, multi: bool) {
+		let next_direction = match self.get(&column) {
+			None => Some(SortDirection::Ascending),
+			Some((_, SortDirection::Ascending)) => Some(SortDirection::Descending),
+			Some((_, SortDirection::Descending)) => None,
+		};
+		if !multi {
+			self.keys.retain(|key| key.column == column);
+		}
+		match next_direction {
+			Some(direction) => match self.keys.iter_mut().find(|key| key.column == column) {
+				Some(key) => key.direction = direction,
+				None => self.keys.push(SortKey { column, direction }),
+			},
+			None => self.keys.retain(|key| key.column != column),
+		}
+	}
+}
+
+/// Sort order of the closest [`Table`].
+#[derive(Clone, Copy)]
+struct TableSort {
+	sort: RwSignal<SortSpec>,
+	offset: RwSignal<u64>,
+}
+
+/// A header cell that sorts its [`Table`] by the given column when clicked.
+///
+/// # Example
+/// ```rust,ignore
+/// <Table
+///     data_source=move |offset, limit, sort| fetch_users(offset, limit, sort)
+///     limit=20
+///     header=move || view! {
+///         <tr>
+///             <SortableHeader column="name">"Name"</SortableHeader>
+///             <th>"Email"</th>
+///         </tr>
+///     }
+///     row=...
+/// />
+/// ```
+#[component]
+pub fn SortableHeader(
+	/// Column id, as passed to the data source in the [`SortSpec`].
+	#[prop(into)]
+	column: std::borrow::Cow<'static, str>,
+	/// Corresponds to the 'class' attribute of elements.
+	#[prop(optional, into)]
+	class: Text,
+	/// Corresponds to the 'style' attribute of elements.
+	#[prop(optional, into)]
+	style: Text,
+	/// Children of the component.
+	children: Children,
+) -> impl IntoView {
+	let TableSort { sort, offset } = expect_context::<TableSort>();
+	let column = StoredValue::new(column);
+	let state = Memo::new(move |_| sort.with(|sort| column.with_value(|column| sort.get(column)).map(|(idx, direction)| (idx, direction, sort.keys().len()))));
+
+	view! {
+		<th
+			class=move || class.get()
+			style=move || style.get().into_owned()
+			aria-sort=move || match state.get() {
+				Some((_, SortDirection::Ascending, _)) => "ascending",
+				Some((_, SortDirection::Descending, _)) => "descending",
+				None => "none",
+			}
+		>
+			<button
+				on:click=move |ev| {
+					if offset.get_untracked() != 0 {
+						offset.set(0);
+					}
+					sort.update(move |sort| sort.cycle(column.get_value(), ev.shift_key()));
+				}
+				class="horizontal vcenter gap-1 autohighlight"
+			>
+				{children()}
+				{move || {
+					let icon_class = match state.get() {
+						Some((_, SortDirection::Ascending, _)) => "i-o-chevron-up",
+						Some((_, SortDirection::Descending, _)) => "i-o-chevron-down",
+						None => "i-o-chevron-up-down opacity-50",
+					};
+					view! { <span class=format!("icon size-4 {icon_class}") /> }
+				}}
+				// position in a multi-column sort
+				{move || state.get().and_then(|(idx, _, len)| (len > 1).then(|| view! { <span class="text-xs">{idx + 1}</span> }))}
+			</button>
+		</th>
 	}
 }
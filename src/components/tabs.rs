@@ -1,5 +1,6 @@
 use leptos::{prelude::*, either::*};
-use crate::utils::Text;
+use web_sys::wasm_bindgen::JsCast;
+use crate::{components::TabGroupsController, utils::{AnchoredPopover, Text}};
 
 #[doc(hidden)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -29,30 +30,85 @@ crate::generate_marker_type!(
 	#[doc(hidden)]
 	SwitchActiveTabMarker
 );
+crate::generate_marker_type!(
+	#[doc(hidden)]
+	MoveTabMarker
+);
+crate::generate_marker_type!(
+	#[doc(hidden)]
+	ReopenClosedTabMarker
+);
 
 pub type TabId = u64;
 pub type TabSignal<T> = RwSignal<TabWithId<T>>;
-pub type AddTab<M, T> = crate::utils::Marked<TabMarker<(M, AddTabMarker)>, Callback<T>>;
+/// Adds a tab, returning its id.
+pub type AddTab<M, T> = crate::utils::Marked<TabMarker<(M, AddTabMarker)>, Callback<T, TabId>>;
 pub type RemoveTab<M> = crate::utils::Marked<TabMarker<(M, RemoveTabMarker)>, Callback<TabId>>;
 pub type ModifyTab<M, T> = crate::utils::Marked<TabMarker<(M, ModifyTabMarker)>, Callback<(TabId, T)>>;
 pub type RemoveTabs<M> = crate::utils::Marked<TabMarker<(M, RemoveTabsMarker)>, Callback<Vec<TabId>>>;
 pub type RemoveOtherTabs<M> = crate::utils::Marked<TabMarker<(M, RemoveOtherTabsMarker)>, Callback<TabId>>;
 pub type SwitchActiveTab<M> = crate::utils::Marked<TabMarker<(M, SwitchActiveTabMarker)>, Callback<TabId>>;
+/// Moves the tab to the given index in the tab strip.
+pub type MoveTab<M> = crate::utils::Marked<TabMarker<(M, MoveTabMarker)>, Callback<(TabId, usize)>>;
+/// Reopens the most recently closed tab and activates it, returning its id if there was one.
+pub type ReopenClosedTab<M> = crate::utils::Marked<TabMarker<(M, ReopenClosedTabMarker)>, Callback<(), Option<TabId>>>;
+
+/// How many closed tabs can be reopened.
+const MAX_CLOSED_TABS: usize = 32;
+/// Data type under which dragged tab ids are stored.
+const TAB_DRAG_MIME: &str = "application/x-wu-tab";
 
 /// Use tabs to quickly switch between different views and pages.
+///
+/// The tab strip can be navigated with the keyboard: Left/Right/Home/End move the focus
+/// between tabs, Enter/Space activate the focused tab and Delete closes it if `closeable` is set.
+///
+/// Provides a [`TabsController<M, T>`] context, along with a context for each of its operations.
+///
+/// # Migration
+/// The `item` slot is wrapped in an `<li role="tab">` which handles activation by itself, so
+/// item slots should no longer render their own `<li>` or a button calling [`SwitchActiveTab`].
+/// Render just the tab's label instead:
+/// ```rust,ignore
+/// // before
+/// item=move |tab: TabSignal<Tab>| view! {
+///     <li><button on:click=move |_| switch_active_tab.run(tab.read().id)>{tab.read().name.clone()}</button></li>
+/// }.into_any()
+/// // after
+/// item=move |tab: TabSignal<Tab>| view! { <span>{tab.read().name.clone()}</span> }.into_any()
+/// ```
 #[component]
 pub fn Tabs<M: Send + Sync + 'static, T: Send + Sync + 'static>(
 	#[prop(optional)] _phant: std::marker::PhantomData<(M, T)>,
 	/// Default list of tab contexts.
+	///
+	/// Ignored if `controller` is provided.
 	#[prop(default = Vec::default(), into)]
 	tabs: Vec<T>,
+	/// Controller for the tabs, used for controlling them from outside of the component.
+	///
+	/// The component creates its own if not provided.
+	#[prop(optional, into)]
+	controller: Option<TabsController<M, T>>,
 	/// Corresponds to the 'class' attribute of elements.
 	#[prop(optional, into)]
 	class: Text,
 	/// List class.
 	#[prop(optional, into)]
 	list_class: Text,
+	/// Can tabs be closed with the Delete key?
+	#[prop(optional)]
+	closeable: bool,
+	/// Can tabs be reordered by dragging them?
+	///
+	/// The tab being dragged is marked with `data-dragging` and the tab it hovers over with `data-drop-target`.
+	#[prop(optional)]
+	reorderable: bool,
 	/// List item slot.
+	///
+	/// Gets wrapped in an `<li role="tab">`, so it must not render an `<li>` or an element
+	/// activating the tab itself. Other controls inside of it, like a close button, keep
+	/// their own keyboard handling.
 	#[prop(into)]
 	item: Callback<TabSignal<T>, AnyView>,
 	/// Content slot.
@@ -64,89 +120,918 @@ pub fn Tabs<M: Send + Sync + 'static, T: Send + Sync + 'static>(
 	/// Content fallback.
 	#[prop(optional, into)]
 	content_fallback: ViewFn,
+	/// Persists the open tabs, their order and the active tab, restoring them on reload.
+	///
+	/// A restored session replaces `tabs`.
+	#[prop(optional, into)]
+	persist: Option<TabsPersistence<T>>,
+	/// When tab content gets mounted and unmounted.
+	#[prop(optional)]
+	content_mode: TabContentMode,
+	/// Maximum amount of tab contents kept mounted at once, unmounting the least recently used ones first.
+	///
+	/// Only applies to [`TabContentMode::KeepAlive`] and [`TabContentMode::Lazy`].
+	#[prop(optional, into)]
+	max_alive: Option<usize>,
+	/// Which tab becomes active when the active tab gets closed.
+	#[prop(optional)]
+	close_activation: TabCloseActivation,
+	/// Activates tabs as soon as they get added.
+	#[prop(optional)]
+	activate_added: bool,
+	/// What happens when there are more tabs than fit into the tab strip.
+	#[prop(optional)]
+	overflow: TabsOverflow,
 ) -> impl IntoView {
-	let is_default_full = !tabs.is_empty();
-	let (tab_id, set_tab_id) = signal::<TabId>(tabs.len() as TabId);
-	let (tabs, set_tabs) = signal::<Vec<TabSignal<T>>>(
-		tabs.into_iter()
-			.enumerate()
-			.map(|(id, tab)| TabWithId { id: id as u64, tab })
-			.map(|tab| RwSignal::new(tab))
-			.collect(),
-	);
-	let (active_tab_id, set_active_tab_id) = signal::<Option<TabId>>(is_default_full.then_some(0));
-	let active_tab = Signal::derive(move || {
-		active_tab_id
-			.get()
-			.and_then(move |active_tab_id| tabs.get().iter().find(|tab| tab.with(|tab| tab.id == active_tab_id)).cloned())
+	let controller = controller.unwrap_or_else(|| TabsController::new(tabs));
+	controller.close_activation.set_value(close_activation);
+	controller.activate_added.set_value(activate_added);
+	let tabs = controller.tabs;
+	let active_tab_id = controller.active_id;
+	// memoized so that the content only re-renders when a different tab becomes active
+	let active_tab = Memo::new(move |_| controller.active());
+
+	provide_context(controller);
+	provide_context(AddTab::<M, T>::new(Callback::new(move |tab| controller.add(tab))));
+	provide_context(RemoveTab::<M>::new(Callback::new(move |id| controller.remove(id))));
+	provide_context(ModifyTab::<M, T>::new(Callback::new(move |(id, tab)| controller.modify(id, tab))));
+	provide_context(RemoveTabs::<M>::new(Callback::new(move |ids: Vec<TabId>| controller.remove_many(&ids))));
+	provide_context(RemoveOtherTabs::<M>::new(Callback::new(move |id| controller.remove_others(id))));
+	provide_context(ReopenClosedTab::<M>::new(Callback::new(move |_| controller.reopen_closed())));
+	provide_context(MoveTab::<M>::new(Callback::new(move |(id, idx)| controller.move_tab(id, idx))));
+	provide_context(SwitchActiveTab::<M>::new(Callback::new(move |id| controller.set_active(id))));
+
+	// persistence
+	if let Some(persist) = persist {
+		if let Some((tabs, active_idx)) = persist.load() {
+			controller.restore(tabs, active_idx);
+		}
+		persist.watch(tabs, active_tab_id);
+	}
+
+	// keep-alive
+	let alive_tab_ids = RwSignal::new(match content_mode {
+		TabContentMode::Active => Vec::default(),
+		TabContentMode::KeepAlive => tabs.with_untracked(|tabs| tabs.iter().map(|tab| tab.with_untracked(|tab| tab.id)).collect()),
+		TabContentMode::Lazy => active_tab_id.get_untracked().into_iter().collect::<Vec<_>>(),
 	});
+	if content_mode != TabContentMode::Active {
+		Effect::new(move |_| {
+			let tab_ids = tabs.with(|tabs| tabs.iter().map(|tab| tab.with_untracked(|tab| tab.id)).collect::<Vec<_>>());
+			let active_tab_id = active_tab_id.get();
+			alive_tab_ids.update(move |alive_tab_ids| {
+				// ordered from the most to the least recently used
+				alive_tab_ids.retain(|id| tab_ids.contains(id));
+				if content_mode == TabContentMode::KeepAlive {
+					alive_tab_ids.extend(tab_ids.iter().filter(|id| !alive_tab_ids.contains(id)).copied().collect::<Vec<_>>());
+				}
+				if let Some(active_tab_id) = active_tab_id {
+					alive_tab_ids.retain(|id| *id != active_tab_id);
+					alive_tab_ids.insert(0, active_tab_id);
+				}
+				if let Some(max_alive) = max_alive {
+					alive_tab_ids.truncate(max_alive.max(1));
+				}
+			});
+		});
+	}
 
-	provide_context(AddTab::<M, T>::new(Callback::new(move |tab| {
-		// add to tabs
-		let id = tab_id.get_untracked();
-		let tab = RwSignal::new(TabWithId { id, tab });
-		set_tabs.update(move |tabs| tabs.push(tab));
-		// autoincrement tab id
-		set_tab_id.update(move |tab_id| *tab_id += 1);
-		// assign active tab if not set
-		if active_tab_id.get_untracked().is_none() {
-			set_active_tab_id.update(move |active_tab_id| *active_tab_id = Some(0))
-		}
-	})));
-	provide_context(RemoveTab::<M>::new(Callback::new(move |id| {
-		set_tabs.update(move |tabs| tabs.retain(|tab| tab.with_untracked(|tab| tab.id != id)));
-		set_active_tab_id.update(move |active_tab_id| *active_tab_id = tabs.with_untracked(|tabs| (!tabs.is_empty()).then_some(tabs[0].with_untracked(|tab| tab.id))));
-	})));
-	provide_context(ModifyTab::<M, T>::new(Callback::new(move |(id, new_tab)| {
-		let new_tab = TabWithId { id, tab: new_tab };
-		tabs.with(move |tabs| {
-			let tab = tabs.iter().find(|tab| tab.with_untracked(|tab| tab.id == id));
-			if let Some(tab) = tab {
-				tab.update(move |tab| *tab = new_tab)
+	// accessibility
+	let id_prefix = StoredValue::new(format!("wu-tabs-{}", uuid::Uuid::new_v4()));
+	let tab_element_id = move |id: TabId| id_prefix.with_value(|id_prefix| format!("{id_prefix}-tab-{id}"));
+	let panel_element_id = move |id: TabId| id_prefix.with_value(|id_prefix| format!("{id_prefix}-panel-{id}"));
+	// overflow
+	let list_ref = NodeRef::<leptos::html::Ul>::new();
+	let strip_tabs = Memo::new(move |_| match overflow {
+		TabsOverflow::Menu { max_visible } => {
+			let mut strip_tabs = tabs.with(|tabs| tabs.iter().take(max_visible).copied().collect::<Vec<_>>());
+			// the active tab always stays in the strip, taking the place of the last one
+			if let Some(active_tab) = active_tab.get() {
+				if !strip_tabs.contains(&active_tab) && !strip_tabs.is_empty() {
+					strip_tabs.pop();
+					strip_tabs.push(active_tab);
+				}
+			}
+			strip_tabs
+		},
+		TabsOverflow::None | TabsOverflow::Scroll => tabs.get(),
+	});
+	let menu_tabs = Memo::new(move |_| {
+		let strip_tabs = strip_tabs.get();
+		tabs.get().into_iter().filter(|tab| !strip_tabs.contains(tab)).collect::<Vec<_>>()
+	});
+	let can_scroll_back = RwSignal::new(false);
+	let can_scroll_forward = RwSignal::new(false);
+	let update_scroll_state = move || {
+		if let Some(list) = list_ref.get_untracked() {
+			let scroll_left = list.scroll_left();
+			can_scroll_back.set(scroll_left > 0);
+			can_scroll_forward.set(scroll_left + list.client_width() < list.scroll_width() - 1);
+		}
+	};
+	let scroll_by = move |direction: i32| {
+		if let Some(list) = list_ref.get_untracked() {
+			list.set_scroll_left(list.scroll_left() + direction * list.client_width() * 3 / 4);
+		}
+	};
+	if overflow == TabsOverflow::Scroll {
+		Effect::new(move |_| {
+			tabs.track();
+			request_animation_frame(update_scroll_state);
+		});
+		_ = leptos_use::use_event_listener(window(), leptos::ev::resize, move |_| update_scroll_state());
+	}
+	// scroll the active tab into view, but only horizontally so that the page does not jump
+	Effect::new(move |_| {
+		let Some(active_tab_id) = active_tab_id.get() else {
+			return;
+		};
+		let Some(list) = list_ref.get() else {
+			return;
+		};
+		request_animation_frame(move || {
+			let Some(tab) = document().get_element_by_id(&tab_element_id(active_tab_id)) else {
+				return;
+			};
+			let (tab_rect, list_rect) = (tab.get_bounding_client_rect(), list.get_bounding_client_rect());
+			let tab_start = (tab_rect.left() - list_rect.left()) as i32 + list.scroll_left();
+			let tab_end = tab_start + tab_rect.width() as i32;
+			let (visible_start, visible_end) = (list.scroll_left(), list.scroll_left() + list.client_width());
+			if tab_start < visible_start {
+				list.set_scroll_left(tab_start);
+			} else if tab_end > visible_end {
+				list.set_scroll_left(tab_end - list.client_width());
 			}
 		});
-	})));
-	provide_context(RemoveTabs::<M>::new(Callback::new(move |tab_ids: Vec<TabId>| {
-		set_tabs.update(move |tabs| tabs.retain(|tab| !tab_ids.contains(&tab.with_untracked(|tab| tab.id))));
-		set_active_tab_id.update(move |active_tab_id| *active_tab_id = tabs.with_untracked(|tabs| (!tabs.is_empty()).then_some(tabs[0].with_untracked(|tab| tab.id))));
-	})));
-	provide_context(RemoveOtherTabs::<M>::new(Callback::new(move |id| {
-		set_tabs.update(move |tabs| tabs.retain(|tab| tab.with_untracked(|tab| tab.id == id)));
-		set_active_tab_id.update(move |active_tab_id| *active_tab_id = tabs.with_untracked(|tabs| (!tabs.is_empty()).then_some(tabs[0].with_untracked(|tab| tab.id))));
-	})));
-	provide_context(SwitchActiveTab::<T>::new(Callback::new(move |id| {
-		// find if the tab exists
-		if tabs.with(move |tabs| !tabs.iter().any(|tab| tab.with_untracked(|tab| tab.id == id))) {
-			log::error!("SwitchActiveTab: tab with id '{id}' does not exist");
-		}
-		// update the active tab id
-		set_active_tab_id.update(move |active_tab_id| *active_tab_id = Some(id))
-	})));
+	});
+	let menu = StoredValue::new(AnchoredPopover::new("wu-tabs-menu"));
+
+	let activate_tab = move |id: TabId| {
+		// the tab might have been removed by a handler inside of the item slot
+		if controller.contains(id) {
+			controller.activate(id);
+		}
+	};
+	let on_tab_keydown = move |ev: leptos::ev::KeyboardEvent, id: TabId| {
+		// leave keys pressed in controls inside of the item slot to those controls
+		if ev.target() != ev.current_target() {
+			return;
+		}
+		// only tabs in the strip can take focus
+		let ids = strip_tabs.with_untracked(|tabs| tabs.iter().map(|tab| tab.with_untracked(|tab| tab.id)).collect::<Vec<_>>());
+		let Some(idx) = ids.iter().position(|tab_id| *tab_id == id) else {
+			return;
+		};
+		let focus_target = match ev.key().as_str() {
+			"ArrowLeft" => Some(ids[(idx + ids.len() - 1) % ids.len()]),
+			"ArrowRight" => Some(ids[(idx + 1) % ids.len()]),
+			"Home" => ids.first().copied(),
+			"End" => ids.last().copied(),
+			"Enter" | " " => {
+				ev.prevent_default();
+				activate_tab(id);
+				None
+			},
+			"Delete" if closeable => {
+				ev.prevent_default();
+				// keep the focus inside of the tab strip
+				let next_id = ids.get(idx + 1).or_else(|| idx.checked_sub(1).and_then(|idx| ids.get(idx))).copied();
+				controller.remove(id);
+				next_id
+			},
+			_ => None,
+		};
+		if let Some(focus_target) = focus_target {
+			ev.prevent_default();
+			if let Some(el) = document().get_element_by_id(&tab_element_id(focus_target)) {
+				_ = el.unchecked_into::<web_sys::HtmlElement>().focus();
+			}
+		}
+	};
+
+	// reordering
+	let dragged_tab_id = RwSignal::new(None::<TabId>);
+	let drop_target_id = RwSignal::new(None::<TabId>);
+	// tabs can also be dragged in from other groups
+	let tab_groups = use_context::<TabGroupsController<M, T>>();
+	let is_tab_drag = move |ev: &leptos::ev::DragEvent| {
+		dragged_tab_id.get_untracked().is_some()
+			|| tab_groups.is_some() && ev.data_transfer().is_some_and(|data_transfer| data_transfer.types().includes(&TAB_DRAG_MIME.into(), 0))
+	};
+	let on_tab_drop = move |ev: leptos::ev::DragEvent, idx: usize| {
+		ev.prevent_default();
+		ev.stop_propagation();
+		if let Some(dragged_id) = dragged_tab_id.get_untracked() {
+			controller.move_tab(dragged_id, idx);
+			return;
+		}
+		let dragged_id = ev
+			.data_transfer()
+			.and_then(|data_transfer| data_transfer.get_data(TAB_DRAG_MIME).ok())
+			.and_then(|data| data.parse::<TabId>().ok());
+		if let (Some(tab_groups), Some(dragged_id)) = (tab_groups, dragged_id) {
+			if let Some(group_id) = tab_groups.group_with_controller(controller) {
+				tab_groups.move_tab(dragged_id, group_id, idx);
+			}
+		}
+	};
+	// the tab might have been dragged out into another group, in which case no `dragend` reaches this component
+	Effect::new(move |_| {
+		if dragged_tab_id.get().is_some_and(|id| !tabs.with(|tabs| tabs.iter().any(|tab| tab.with_untracked(|tab| tab.id == id)))) {
+			dragged_tab_id.set(None);
+			drop_target_id.set(None);
+		}
+	});
 
 	view! {
 		<wu-tabs class=move || class.get()>
-			<ul class=move || list_class.get()>
+			<div class=if overflow == TabsOverflow::None { "contents" } else { "horizontal vcenter" }>
+			{(overflow == TabsOverflow::Scroll).then(move || view! {
+				<button
+					aria-label="Scroll tabs back"
+					tabindex="-1"
+					disabled=move || !can_scroll_back.get()
+					on:click=move |_| scroll_by(-1)
+					class="flex-none btn-icon autohighlight size-8 disabled:invisible"
+				>
+					<span class="icon i-o-chevron-left" />
+				</button>
+			})}
+			<ul
+				node_ref=list_ref
+				role="tablist"
+				on:scroll=move |_| update_scroll_state()
+				// dropping onto the strip itself appends the tab
+				on:dragover=move |ev| {
+					if is_tab_drag(&ev) {
+						ev.prevent_default();
+					}
+				}
+				on:drop=move |ev| on_tab_drop(ev, usize::MAX)
+				class=move || match overflow {
+					TabsOverflow::Scroll => format!("relative min-w-0 overflow-x-auto hide-scrollbar scroll-shadow-x {list_class}"),
+					TabsOverflow::Menu { .. } => format!("relative min-w-0 {list_class}"),
+					TabsOverflow::None => list_class.get().to_string(),
+				}
+			>
 				<Show
 					when=move || !tabs.get().is_empty()
 					fallback=list_fallback
 				>
 					<For
-						each=move || tabs.get()
+						each=move || strip_tabs.get()
 						key=move |tab| tab.read().id
 						let:tab
 					>
-						{move || item.run(tab)}
+						{
+							let id = tab.with_untracked(|tab| tab.id);
+							let is_active = Memo::new(move |_| active_tab_id.get() == Some(id));
+							view! {
+								<li
+									role="tab"
+									id=tab_element_id(id)
+									aria-controls=panel_element_id(id)
+									aria-selected=move || is_active.get().to_string()
+									tabindex=move || if is_active.get() { "0" } else { "-1" }
+									on:click=move |_| activate_tab(id)
+									on:keydown=move |ev| on_tab_keydown(ev, id)
+									draggable=reorderable.then_some("true")
+									data-dragging=move || (dragged_tab_id.get() == Some(id)).then_some("")
+									data-drop-target=move || (drop_target_id.get() == Some(id) && dragged_tab_id.get() != Some(id)).then_some("")
+									on:dragstart=move |ev| {
+										if !reorderable {
+											return;
+										}
+										if let Some(data_transfer) = ev.data_transfer() {
+											// some browsers refuse to start dragging without any data
+											_ = data_transfer.set_data("text/plain", &id.to_string());
+											_ = data_transfer.set_data(TAB_DRAG_MIME, &id.to_string());
+											data_transfer.set_effect_allowed("move");
+										}
+										dragged_tab_id.set(Some(id));
+									}
+									on:dragover=move |ev| {
+										if is_tab_drag(&ev) {
+											ev.prevent_default();
+											ev.stop_propagation();
+											drop_target_id.set(Some(id));
+										}
+									}
+									on:dragleave=move |_| drop_target_id.update(move |target| if *target == Some(id) { *target = None })
+									on:drop=move |ev| {
+										drop_target_id.set(None);
+										if let Some(idx) = tabs.with_untracked(|tabs| tabs.iter().position(|tab| tab.with_untracked(|tab| tab.id == id))) {
+											on_tab_drop(ev, idx);
+										}
+									}
+									on:dragend=move |_| {
+										dragged_tab_id.set(None);
+										drop_target_id.set(None);
+									}
+								>
+									{move || item.run(tab)}
+								</li>
+							}
+						}
 					</For>
 				</Show>
 			</ul>
-			{move || match active_tab.get() {
-				None => Either::Left(content_fallback.run()),
-				Some(tab) => Either::Right(content.run(tab)),
+			{(overflow == TabsOverflow::Scroll).then(move || view! {
+				<button
+					aria-label="Scroll tabs forward"
+					tabindex="-1"
+					disabled=move || !can_scroll_forward.get()
+					on:click=move |_| scroll_by(1)
+					class="flex-none btn-icon autohighlight size-8 disabled:invisible"
+				>
+					<span class="icon i-o-chevron-right" />
+				</button>
+			})}
+			{matches!(overflow, TabsOverflow::Menu { .. }).then(move || {
+				view! {
+					<Show when=move || menu_tabs.with(|tabs| !tabs.is_empty())>
+						<button
+							popovertarget=menu.with_value(|menu| menu.id.clone())
+							aria-label="More tabs"
+							aria-haspopup="menu"
+							style=menu.with_value(|menu| menu.anchor_style.clone())
+							class="flex-none horizontal vcenter gap-1 btn-icon autohighlight h-8 px-2"
+						>
+							<span class="icon i-o-ellipsis-horizontal" />
+							<span class="text-xs">{move || menu_tabs.with(Vec::len)}</span>
+						</button>
+					</Show>
+					<ul
+						id=menu.with_value(|menu| menu.id.clone())
+						popover
+						role="menu"
+						style=menu.with_value(|menu| menu.popover_style.clone())
+						class="vertical m-0 max-h-[50vh] overflow-y-auto p-1 border bg-surface-1 border-surface-2 shadow-lg rounded-md"
+					>
+						<For
+							each=move || menu_tabs.get()
+							key=move |tab| tab.with_untracked(|tab| tab.id)
+							let:tab
+						>
+							{
+								let id = tab.with_untracked(|tab| tab.id);
+								view! {
+									<li
+										role="menuitem"
+										tabindex="0"
+										on:click=move |_| {
+											activate_tab(id);
+											menu.with_value(AnchoredPopover::hide);
+										}
+										on:keydown=move |ev| {
+											if ev.key() == "Enter" || ev.key() == " " {
+												ev.prevent_default();
+												activate_tab(id);
+												menu.with_value(AnchoredPopover::hide);
+											}
+										}
+										class="autohighlight rounded-md"
+									>
+										{move || item.run(tab)}
+									</li>
+								}
+							}
+						</For>
+					</ul>
+				}
+			})}
+			</div>
+			{match content_mode {
+				TabContentMode::Active => Either::Left(move || match active_tab.get() {
+					None => Either::Left(content_fallback.run()),
+					Some(tab) => {
+						let id = tab.with_untracked(|tab| tab.id);
+						Either::Right(view! {
+							<div role="tabpanel" id=panel_element_id(id) aria-labelledby=tab_element_id(id) tabindex="0">
+								{content.run(tab)}
+							</div>
+						})
+					},
+				}),
+				TabContentMode::KeepAlive | TabContentMode::Lazy => Either::Right(view! {
+					<Show when=move || active_tab_id.get().is_none()>
+						{content_fallback.run()}
+					</Show>
+					<For
+						each=move || {
+							let alive_tab_ids = alive_tab_ids.get();
+							tabs.get().into_iter().filter(move |tab| alive_tab_ids.contains(&tab.with_untracked(|tab| tab.id))).collect::<Vec<_>>()
+						}
+						key=move |tab| tab.with_untracked(|tab| tab.id)
+						let:tab
+					>
+						{
+							let id = tab.with_untracked(|tab| tab.id);
+							view! {
+								<div
+									role="tabpanel"
+									id=panel_element_id(id)
+									aria-labelledby=tab_element_id(id)
+									tabindex="0"
+									hidden=move || active_tab_id.get() != Some(id)
+								>
+									{content.run(tab)}
+								</div>
+							}
+						}
+					</For>
+				}),
 			}}
 		</wu-tabs>
 	}
 }
 
+/// A handle for controlling [`Tabs`], provided as a context by the component.
+///
+/// Can also be created outside of the component and passed to it, which allows
+/// the parent component to control the tabs.
+///
+/// # Example
+/// ```rust,ignore
+/// let controller = TabsController::<MyApp, Document>::new([Document::default()]);
+/// view! {
+///     <button on:click=move |_| _ = controller.add(Document::default())>"New document"</button>
+///     <span>{move || format!("{} open", controller.len())}</span>
+///     <Tabs<MyApp, Document> controller item=... content=... />
+/// }
+/// ```
+pub struct TabsController<M, T>
+where
+	T: Send + Sync + 'static,
+{
+	pub(crate) tabs: RwSignal<Vec<TabSignal<T>>>,
+	pub(crate) active_id: RwSignal<Option<TabId>>,
+	next_id: RwSignal<TabId>,
+	/// Ordered from the most to the least recently used.
+	recently_used_ids: StoredValue<Vec<TabId>>,
+	/// Closed tabs along with their former index.
+	closed_tabs: StoredValue<Vec<(usize, TabSignal<T>)>>,
+	close_activation: StoredValue<TabCloseActivation>,
+	activate_added: StoredValue<bool>,
+	/// Tab state must outlive whichever component added the tab.
+	owner: StoredValue<Owner>,
+	_phant: std::marker::PhantomData<M>,
+}
+
+impl<M, T> Clone for TabsController<M, T>
+where
+	T: Send + Sync + 'static,
+{
+	fn clone(&self) -> Self {
+		*self
+	}
+}
+
+impl<M, T> Copy for TabsController<M, T> where T: Send + Sync + 'static {}
+
+impl<M, T> PartialEq for TabsController<M, T>
+where
+	T: Send + Sync + 'static,
+{
+	fn eq(&self, other: &Self) -> bool {
+		self.tabs == other.tabs
+	}
+}
+
+impl<M, T> Eq for TabsController<M, T> where T: Send + Sync + 'static {}
+
+impl<M, T> TabsController<M, T>
+where
+	M: Send + Sync + 'static,
+	T: Send + Sync + 'static,
+{
+	/// Creates a controller with the given tabs, the first of which is active.
+	pub fn new(tabs: impl IntoIterator<Item = T>) -> Self {
+		Self::with_next_id(RwSignal::new(0), Owner::current().expect("should be called inside a component"), tabs)
+	}
+
+	/// Creates a controller which takes tab ids from the given counter, so that multiple controllers can share an id space.
+	///
+	/// Tabs are created under the given owner, so that they can outlive the controller when moved to another one.
+	pub(crate) fn with_next_id(next_id: RwSignal<TabId>, tab_owner: Owner, tabs: impl IntoIterator<Item = T>) -> Self {
+		let controller = Self {
+			tabs: RwSignal::new(Vec::default()),
+			active_id: RwSignal::new(None),
+			next_id,
+			recently_used_ids: StoredValue::new(Vec::default()),
+			closed_tabs: StoredValue::new(Vec::default()),
+			close_activation: StoredValue::new(TabCloseActivation::default()),
+			activate_added: StoredValue::new(false),
+			owner: StoredValue::new(tab_owner),
+			_phant: Default::default(),
+		};
+		for tab in tabs {
+			controller.add(tab);
+		}
+		controller
+	}
+
+	/// Adds a tab, returning its id.
+	///
+	/// The tab becomes active if no other tab is active or if `activate_added` is set on [`Tabs`].
+	pub fn add(&self, tab: T) -> TabId {
+		let id = self.next_id.get_untracked();
+		self.next_id.set(id + 1);
+		let tab = self.owner.get_value().with(move || RwSignal::new(TabWithId { id, tab }));
+		self.tabs.update(move |tabs| tabs.push(tab));
+		if self.activate_added.get_value() || self.active_id.get_untracked().is_none() {
+			self.activate(id);
+		}
+		id
+	}
+
+	/// Removes the tab.
+	pub fn remove(&self, id: TabId) {
+		self.remove_many(&[id]);
+	}
+
+	/// Removes all given tabs.
+	pub fn remove_many(&self, ids: &[TabId]) {
+		let closed = self.detach(ids);
+		if closed.is_empty() {
+			return;
+		}
+		let mut evicted = Vec::new();
+		self.closed_tabs.update_value(|closed_tabs| {
+			closed_tabs.extend(closed);
+			let excess = closed_tabs.len().saturating_sub(MAX_CLOSED_TABS);
+			evicted = closed_tabs.drain(..excess).map(|(_, tab)| tab).collect();
+		});
+		dispose_tabs(evicted);
+	}
+
+	/// Takes the given tabs out of the controller without remembering them as closed,
+	/// returning them along with their former index.
+	pub(crate) fn detach(&self, ids: &[TabId]) -> Vec<(usize, TabSignal<T>)> {
+		let prev_ids = self.ids_untracked();
+		let mut detached = Vec::new();
+		self.tabs.update(|tabs| {
+			let mut idx = 0;
+			tabs.retain(|tab| {
+				let is_detached = tab.with_untracked(|tab| ids.contains(&tab.id));
+				if is_detached {
+					detached.push((idx, *tab));
+				}
+				idx += 1;
+				!is_detached
+			});
+		});
+		if detached.is_empty() {
+			return detached;
+		}
+		self.recently_used_ids.update_value(|recently_used_ids| recently_used_ids.retain(|id| !ids.contains(id)));
+
+		// the active tab stays active if it is still open
+		let active_id = self.active_id.get_untracked();
+		if active_id.is_some_and(|active_id| !ids.contains(&active_id)) {
+			return detached;
+		}
+		let remaining_ids = self.ids_untracked();
+		let next_id = match self.close_activation.get_value() {
+			TabCloseActivation::Neighbour => active_id.and_then(|active_id| prev_ids.iter().position(|id| *id == active_id)).and_then(|idx| {
+				// prefer the closest tab to the right, then the closest to the left
+				prev_ids[idx + 1..]
+					.iter()
+					.chain(prev_ids[..idx].iter().rev())
+					.find(|id| remaining_ids.contains(id))
+					.copied()
+			}),
+			TabCloseActivation::MostRecentlyUsed => self.recently_used_ids.with_value(|recently_used_ids| recently_used_ids.first().copied()),
+		};
+		match next_id.or_else(|| remaining_ids.first().copied()) {
+			Some(next_id) => self.activate(next_id),
+			None => self.active_id.set(None),
+		}
+		detached
+	}
+
+	/// Inserts a detached tab at the given index and activates it.
+	pub(crate) fn insert(&self, tab: TabSignal<T>, idx: usize) {
+		self.tabs.update(move |tabs| tabs.insert(idx.min(tabs.len()), tab));
+		self.activate(tab.with_untracked(|tab| tab.id));
+	}
+
+	/// Removes all tabs except the given one.
+	pub fn remove_others(&self, id: TabId) {
+		let other_ids = self.ids_untracked().into_iter().filter(|tab_id| *tab_id != id).collect::<Vec<_>>();
+		self.remove_many(&other_ids);
+	}
+
+	/// Replaces the tab's context.
+	pub fn modify(&self, id: TabId, tab: T) {
+		match self.get_untracked(id) {
+			Some(tab_signal) => tab_signal.update(move |tab_with_id| tab_with_id.tab = tab),
+			None => log::error!("TabsController::modify: tab with id '{id}' does not exist"),
+		}
+	}
+
+	/// Makes the tab active.
+	pub fn set_active(&self, id: TabId) {
+		if !self.contains(id) {
+			log::error!("TabsController::set_active: tab with id '{id}' does not exist");
+			return;
+		}
+		self.activate(id);
+	}
+
+	/// Moves the tab to the given index in the tab strip.
+	pub fn move_tab(&self, id: TabId, idx: usize) {
+		self.tabs.update(move |tabs| {
+			let Some(from) = tabs.iter().position(|tab| tab.with_untracked(|tab| tab.id == id)) else {
+				log::error!("TabsController::move_tab: tab with id '{id}' does not exist");
+				return;
+			};
+			let tab = tabs.remove(from);
+			tabs.insert(idx.min(tabs.len()), tab);
+		});
+	}
+
+	/// Reopens the most recently closed tab and activates it, returning its id if there was one.
+	pub fn reopen_closed(&self) -> Option<TabId> {
+		let (idx, tab) = self.closed_tabs.try_update_value(Vec::pop).flatten()?;
+		self.insert(tab, idx);
+		Some(tab.with_untracked(|tab| tab.id))
+	}
+
+	/// Gets the id of the active tab.
+	pub fn active_id(&self) -> Option<TabId> {
+		self.active_id.get()
+	}
+
+	/// Gets the active tab.
+	pub fn active(&self) -> Option<TabSignal<T>> {
+		self.active_id.get().and_then(|id| self.get(id))
+	}
+
+	/// Gets the ids of all tabs, in order.
+	pub fn ids(&self) -> Vec<TabId> {
+		self.tabs.with(|tabs| tabs.iter().map(|tab| tab.with_untracked(|tab| tab.id)).collect())
+	}
+
+	/// Gets all tabs, in order.
+	pub fn tabs(&self) -> Vec<TabSignal<T>> {
+		self.tabs.get()
+	}
+
+	/// Gets the tab with the given id.
+	pub fn get(&self, id: TabId) -> Option<TabSignal<T>> {
+		self.tabs.with(|tabs| tabs.iter().find(|tab| tab.with_untracked(|tab| tab.id == id)).copied())
+	}
+
+	/// Gets the number of tabs.
+	pub fn len(&self) -> usize {
+		self.tabs.with(Vec::len)
+	}
+
+	/// Are there no tabs?
+	pub fn is_empty(&self) -> bool {
+		self.tabs.with(Vec::is_empty)
+	}
+
+	pub(crate) fn contains(&self, id: TabId) -> bool {
+		self.get_untracked(id).is_some()
+	}
+
+	fn get_untracked(&self, id: TabId) -> Option<TabSignal<T>> {
+		self.tabs.with_untracked(|tabs| tabs.iter().find(|tab| tab.with_untracked(|tab| tab.id == id)).copied())
+	}
+
+	pub(crate) fn ids_untracked(&self) -> Vec<TabId> {
+		self.tabs.with_untracked(|tabs| tabs.iter().map(|tab| tab.with_untracked(|tab| tab.id)).collect())
+	}
+
+	/// Makes the tab active without checking if it exists.
+	fn activate(&self, id: TabId) {
+		self.active_id.set(Some(id));
+		self.recently_used_ids.update_value(move |ids| {
+			ids.retain(|recent_id| *recent_id != id);
+			ids.insert(0, id);
+		});
+	}
+
+	/// Removes all tabs, including the closed ones, and frees them.
+	pub(crate) fn clear(&self) {
+		let mut tabs = self.tabs.try_update(std::mem::take).unwrap_or_default();
+		tabs.extend(self.closed_tabs.try_update_value(std::mem::take).unwrap_or_default().into_iter().map(|(_, tab)| tab));
+		self.active_id.set(None);
+		self.recently_used_ids.set_value(Vec::default());
+		dispose_tabs(tabs);
+	}
+
+	/// Replaces all tabs with a restored session.
+	pub(crate) fn restore(&self, tabs: Vec<T>, active_idx: Option<usize>) {
+		self.clear();
+		let ids = tabs.into_iter().map(|tab| self.add(tab)).collect::<Vec<_>>();
+		if let Some(id) = active_idx.and_then(|idx| ids.get(idx)) {
+			self.activate(*id);
+		}
+	}
+}
+
+/// What [`Tabs`] do when there are more tabs than fit into the tab strip.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TabsOverflow {
+	/// The tab strip overflows, it is up to the `list_class` to handle it.
+	#[default]
+	None,
+	/// The tab strip scrolls horizontally, with arrows for scrolling and shadows on the overflowing edges.
+	Scroll,
+	/// Tabs beyond the first `max_visible` get collapsed into a "more" menu.
+	Menu {
+		/// How many tabs are displayed in the strip.
+		max_visible: usize,
+	},
+}
+
+/// Which tab [`Tabs`] activate once the active tab gets closed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TabCloseActivation {
+	/// The closest tab to the right, or to the left if there is none.
+	#[default]
+	Neighbour,
+	/// The most recently active tab.
+	MostRecentlyUsed,
+}
+
+/// When [`Tabs`] mount and unmount tab content.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TabContentMode {
+	/// Only the active tab's content is mounted, inactive tabs lose their state.
+	#[default]
+	Active,
+	/// Every tab's content is mounted right away and inactive ones are hidden.
+	KeepAlive,
+	/// A tab's content is mounted on its first activation and hidden once it becomes inactive.
+	Lazy,
+}
+
+/// Where a [`Tabs`] session gets persisted to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TabsStorage {
+	/// Local storage, under the given key.
+	LocalStorage(std::borrow::Cow<'static, str>),
+	/// The URL query parameter with the given key.
+	Query(&'static str),
+}
+
+/// Persists a [`Tabs`] session, i.e. the open tabs, their order and the active tab.
+///
+/// # Example
+/// ```rust,ignore
+/// <Tabs<MyApp, Document>
+///     persist=TabsPersistence::local_storage("editor")
+///     item=...
+///     content=...
+/// />
+/// ```
+pub struct TabsPersistence<T> {
+	storage: TabsStorage,
+	serialize: fn(&[&T], Option<usize>) -> serde_json::Result<String>,
+	deserialize: fn(&str) -> serde_json::Result<TabsSession<T>>,
+}
+
+impl<T> Clone for TabsPersistence<T> {
+	fn clone(&self) -> Self {
+		Self {
+			storage: self.storage.clone(),
+			serialize: self.serialize,
+			deserialize: self.deserialize,
+		}
+	}
+}
+
+impl<T> TabsPersistence<T>
+where
+	T: serde::Serialize + serde::de::DeserializeOwned,
+{
+	/// Persists the session to the given storage.
+	pub fn new(storage: TabsStorage) -> Self {
+		Self {
+			storage,
+			serialize: |tabs, active| {
+				serde_json::to_string(&TabsSessionRef {
+					tabs: tabs.to_vec(),
+					active,
+				})
+			},
+			deserialize: |s| serde_json::from_str::<SerializedTabsSession<T>>(s).map(|session| (session.tabs, session.active)),
+		}
+	}
+
+	/// Persists the session to local storage, under the given key.
+	pub fn local_storage(key: impl Into<std::borrow::Cow<'static, str>>) -> Self {
+		Self::new(TabsStorage::LocalStorage(key.into()))
+	}
+
+	/// Persists the session to the URL query parameter with the given key.
+	pub fn query(key: &'static str) -> Self {
+		Self::new(TabsStorage::Query(key))
+	}
+}
+
+impl<T> TabsPersistence<T> {
+	/// Loads the persisted session, if there is one.
+	fn load(&self) -> Option<TabsSession<T>> {
+		let serialized = match &self.storage {
+			TabsStorage::LocalStorage(key) => get_tabs_session_from_local_storage(key)?,
+			TabsStorage::Query(key) => leptos_router::hooks::use_location().query.with_untracked(|query| query.get_str(key).map(String::from))?,
+		};
+		match (self.deserialize)(&serialized) {
+			Ok(session) => Some(session),
+			Err(err) => {
+				log::error!("could not parse persisted tabs session: {err}");
+				None
+			},
+		}
+	}
+
+	/// Saves the session every time it changes.
+	fn watch(self, tabs: RwSignal<Vec<TabSignal<T>>>, active_tab_id: RwSignal<Option<TabId>>)
+	where
+		T: Send + Sync + 'static,
+	{
+		let query = match self.storage {
+			TabsStorage::Query(_) => Some((leptos_router::hooks::use_location(), crate::utils::use_copy_navigate())),
+			TabsStorage::LocalStorage(_) => None,
+		};
+
+		Effect::new(move |_| {
+			let active_tab_id = active_tab_id.get();
+			let serialized = tabs.with(|tabs| {
+				let guards = tabs.iter().map(|tab| tab.read()).collect::<Vec<_>>();
+				let active_idx = active_tab_id.and_then(|active_tab_id| guards.iter().position(|tab| tab.id == active_tab_id));
+				(self.serialize)(&guards.iter().map(|tab| &tab.tab).collect::<Vec<_>>(), active_idx)
+			});
+			let serialized = match serialized {
+				Ok(serialized) => serialized,
+				Err(err) => {
+					log::error!("could not serialize tabs session: {err}");
+					return;
+				},
+			};
+
+			match (&self.storage, &query) {
+				(TabsStorage::LocalStorage(key), _) => set_tabs_session_to_local_storage(key, &serialized),
+				(TabsStorage::Query(key), Some((location, navigate))) => {
+					if location.query.with_untracked(|query| query.get_str(key) == Some(&serialized)) {
+						return;
+					}
+					// session changes should not pollute the history
+					crate::utils::replace_query(location, *navigate, |query| query.replace(*key, serialized), true);
+				},
+				(TabsStorage::Query(_), None) => unreachable!("query hooks are set up for query storage"),
+			}
+		});
+	}
+}
+
+/// A persisted session: the tabs in order and the index of the active tab.
+type TabsSession<T> = (Vec<T>, Option<usize>);
+
+#[derive(serde::Serialize)]
+struct TabsSessionRef<'a, T> {
+	tabs: Vec<&'a T>,
+	active: Option<usize>,
+}
+
+#[derive(serde::Deserialize)]
+struct SerializedTabsSession<T> {
+	tabs: Vec<T>,
+	active: Option<usize>,
+}
+
+const TABS_SESSION_NAME: &str = "wu-tabs-session";
+
+/// Frees tabs which can no longer be reopened.
+///
+/// Their views still read them until they get removed on the next render.
+fn dispose_tabs<T>(tabs: Vec<TabSignal<T>>)
+where
+	T: Send + Sync + 'static,
+{
+	if tabs.is_empty() {
+		return;
+	}
+	request_animation_frame(move || {
+		for tab in tabs {
+			tab.dispose();
+		}
+	});
+}
+
+fn get_tabs_session_from_local_storage(key: &str) -> Option<String> {
+	crate::utils::get_from_local_storage(&format!("{TABS_SESSION_NAME}-{key}"))
+}
+
+fn set_tabs_session_to_local_storage(key: &str, session: &str) {
+	crate::utils::set_to_local_storage(&format!("{TABS_SESSION_NAME}-{key}"), session);
+}
+
 /// A wrapper around a Tab that holds its ID.
 ///
 /// Used for keyed-for.
@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::{borrow::Cow, time::Duration};
 
 use leptos::{leptos_dom::helpers::TimeoutHandle, prelude::*};
 
@@ -8,19 +8,69 @@ use crate::utils::{Position, Text};
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ToastLevel {
 	Info,
+	Success,
 	Warn,
 	Error,
+	/// A level with a custom look.
+	Custom {
+		/// Icon class, e.g. `i-o-bell`.
+		icon_class: &'static str,
+		/// Icon color class, e.g. `icon-primary-700`.
+		color_class: &'static str,
+		/// Should screen readers interrupt the user to announce the toast?
+		assertive: bool,
+	},
 }
 
 /// A message to display for a set amount of time.
 #[derive(Clone)]
 pub struct Toast {
-	/// Toast view.
+	/// Toast level.
+	level: ToastLevel,
+	/// Toast content view.
 	view: ViewFn,
 	/// Duration of the close timeout.
 	timeout: Duration,
 	/// Is the toast dismissable?
 	dismissable: bool,
+	/// Progress of the operation the toast is describing, from `0.0` to `1.0`.
+	progress: Option<f32>,
+	/// Actions the user can take on the toast.
+	actions: Vec<ToastAction>,
+	/// Is the operation the toast is describing still running?
+	pending: bool,
+	/// Deduplication key.
+	key: Option<Cow<'static, str>>,
+}
+
+/// A labelled button displayed inside of a toast.
+///
+/// Clicking the button runs the callback and dismisses the toast.
+#[derive(Clone)]
+pub struct ToastAction {
+	/// Button label.
+	label: Text,
+	/// Logic to run when the button gets clicked.
+	callback: Callback<()>,
+	/// Does the button stand out from the rest?
+	primary: bool,
+}
+
+impl ToastAction {
+	/// Creates a toast action.
+	pub fn new(label: impl Into<Text>, callback: impl Into<Callback<()>>) -> Self {
+		Self {
+			label: label.into(),
+			callback: callback.into(),
+			primary: false,
+		}
+	}
+
+	/// Makes the button stand out from the rest.
+	pub fn primary(mut self) -> Self {
+		self.primary = true;
+		self
+	}
 }
 
 impl Toast {
@@ -33,26 +83,23 @@ impl Toast {
 
 	/// Creates a toast from a view with custom options.
 	pub fn from_view_with(level: ToastLevel, view_fn: impl Into<ViewFn>, timeout: Duration, dismissable: bool) -> Self {
-		let (icon_class, color_class) = match level {
-			ToastLevel::Info => ("i-o-info-circle", ""),
-			ToastLevel::Warn => ("i-o-exclamation-circle", "icon-warning-700 dark:icon-warning-500"),
-			ToastLevel::Error => ("i-o-exclamation-triangle", "icon-error-700 dark:icon-error-500"),
-		};
 		let view_fn = view_fn.into();
 
 		Self {
+			level,
 			view: ViewFn::from(move || {
 				view! {
-					<div class="horizontal vcenter gap-4">
-						<span class=format!("flex-none size-6 icon {icon_class} {color_class}") />
-						<div class="grow overflow-hidden">
-							{view_fn.run()}
-						</div>
+					<div class="grow overflow-hidden">
+						{view_fn.run()}
 					</div>
 				}
 			}),
 			timeout,
 			dismissable,
+			progress: None,
+			actions: Vec::default(),
+			pending: false,
+			key: None,
 		}
 	}
 
@@ -63,26 +110,58 @@ impl Toast {
 
 	/// Creates a toast from text with custom options.
 	pub fn from_text_with(level: ToastLevel, text: impl Into<Text>, timeout: Duration, dismissable: bool) -> Self {
-		let (icon_class, color_class) = match level {
-			ToastLevel::Info => ("i-o-info-circle", ""),
-			ToastLevel::Warn => ("i-o-exclamation-circle", "icon-warning-700 dark:icon-warning-500"),
-			ToastLevel::Error => ("i-o-exclamation-triangle", "icon-error-700 dark:icon-error-500"),
-		};
 		let text = text.into();
 
 		Self {
+			level,
 			view: ViewFn::from(move || {
 				view! {
-					<div class="horizontal vcenter gap-4">
-						<span class=format!("flex-none icon size-6 {icon_class} {color_class}") />
-						<p class="grow overflow-hidden">{text}</p>
-					</div>
+					<p class="grow overflow-hidden">{text}</p>
 				}
 			}),
 			timeout,
 			dismissable,
+			progress: None,
+			actions: Vec::default(),
+			pending: false,
+			key: None,
 		}
 	}
+
+	/// Displays a progress bar on the toast.
+	pub fn with_progress(mut self, progress: f32) -> Self {
+		self.progress = Some(progress.clamp(0.0, 1.0));
+		self
+	}
+
+	/// Marks the toast as pending.
+	///
+	/// Pending toasts display a spinner and do not time out until they get replaced through [`ToastHandle::update`].
+	pub fn pending(mut self) -> Self {
+		self.pending = true;
+		self
+	}
+
+	/// Sets the deduplication key of the toast.
+	///
+	/// Pushing a toast with the same key as a toast that is still displayed replaces
+	/// the displayed toast, restarts its timeout and increments its count instead.
+	pub fn with_key(mut self, key: impl Into<Cow<'static, str>>) -> Self {
+		self.key = Some(key.into());
+		self
+	}
+
+	/// Adds an action button to the toast.
+	///
+	/// # Example
+	/// ```rust,ignore
+	/// let toast = Toast::from_text(ToastLevel::Info, "Message deleted.")
+	///     .with_action(ToastAction::new("Undo", move |_| restore_message()));
+	/// ```
+	pub fn with_action(mut self, action: ToastAction) -> Self {
+		self.actions.push(action);
+		self
+	}
 }
 
 #[doc(hidden)]
@@ -94,21 +173,268 @@ crate::generate_marker_type!(
 	PushToastMarker
 );
 
+crate::generate_marker_type!(
+	#[doc(hidden)]
+	ToastHistoryMarker
+);
+
 pub type PushToast<M> = crate::utils::Marked<ToastMarker<(M, PushToastMarker)>, Callback<Toast, ToastHandle>>;
+pub type ToastHistory<M> = crate::utils::Marked<ToastMarker<(M, ToastHistoryMarker)>, NotificationHistory>;
 
-/// A type that allows cancelling a toast.
+/// A record of a toast that has been displayed.
+#[derive(Clone)]
+pub struct Notification {
+	id: u64,
+	level: ToastLevel,
+	view: ViewFn,
+	/// When the toast was last pushed or updated, in milliseconds since the epoch.
+	timestamp: f64,
+	read: bool,
+}
+
+impl Notification {
+	/// Gets the level of the notification.
+	pub fn level(&self) -> ToastLevel {
+		self.level
+	}
+
+	/// Gets the content view of the notification.
+	pub fn view(&self) -> ViewFn {
+		self.view.clone()
+	}
+
+	/// Gets when the notification was last pushed or updated, in milliseconds since the epoch.
+	pub fn timestamp(&self) -> f64 {
+		self.timestamp
+	}
+
+	/// Has the notification been read?
+	pub fn is_read(&self) -> bool {
+		self.read
+	}
+}
+
+/// History of all toasts displayed by a [`ToastHook`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotificationHistory {
+	notifications: RwSignal<Vec<Notification>>,
+}
+
+impl NotificationHistory {
+	/// Gets all recorded notifications, from oldest to newest.
+	pub fn notifications(&self) -> Vec<Notification> {
+		self.notifications.get()
+	}
+
+	/// Gets the number of unread notifications.
+	pub fn unread_count(&self) -> usize {
+		self.notifications.with(|notifications| notifications.iter().filter(|notification| !notification.read).count())
+	}
+
+	/// Marks the notification with the given id as read.
+	pub fn mark_read(&self, id: u64) {
+		self.notifications.update(move |notifications| {
+			if let Some(notification) = notifications.iter_mut().find(|notification| notification.id == id) {
+				notification.read = true;
+			}
+		});
+	}
+
+	/// Marks all notifications as read.
+	pub fn mark_all_read(&self) {
+		self.notifications.update(|notifications| notifications.iter_mut().for_each(|notification| notification.read = true));
+	}
+
+	/// Removes all notifications.
+	pub fn clear(&self) {
+		self.notifications.update(Vec::clear);
+	}
+
+	/// Records a newly pushed or updated toast as unread.
+	fn record(&self, id: u64, toast: &Toast) {
+		let notification = Notification {
+			id,
+			level: toast.level,
+			view: toast.view.clone(),
+			timestamp: now(),
+			read: false,
+		};
+		self.notifications.update(move |notifications| {
+			// updated toasts move to the end, as they are the newest
+			notifications.retain(|notification| notification.id != id);
+			notifications.push(notification);
+		});
+	}
+}
+
+/// A bell button listing all notifications recorded by a [`ToastHook`].
+///
+/// Requires the hook to have `history` enabled.
+///
+/// # Example
+/// ```rust,ignore
+/// <ToastHook<MyApp> history=true>
+///     <Shell<MyApp> header=move || view! { <NotificationCenter<MyApp> /> }>
+///         ...
+///     </Shell<MyApp>>
+/// </ToastHook<MyApp>>
+/// ```
+#[component]
+pub fn NotificationCenter<M>(
+	#[prop(optional)] _phant: std::marker::PhantomData<M>,
+	/// Bell button class.
+	#[prop(optional, into)]
+	class: Text,
+	/// Notification panel class.
+	#[prop(default = "top-14 right-2".into(), into)]
+	panel_class: Text,
+) -> impl IntoView
+where
+	M: Send + Sync + 'static,
+{
+	let history = *expect_context::<ToastHistory<M>>();
+	let panel_id = format!("wu-notification-center-{}", uuid::Uuid::new_v4());
+	let unread_count = Memo::new(move |_| history.unread_count());
+
+	view! {
+		<button
+			popovertarget=panel_id.clone()
+			aria-label="Notifications"
+			class=move || format!("relative btn-icon autohighlight size-8 {class}")
+		>
+			<span class="icon i-o-bell" />
+			{move || (unread_count.get() > 0).then(move || view! {
+				<span class="absolute -top-1 -right-1 badge-icon size-4 bg-error-600 text-white">{unread_count}</span>
+			})}
+		</button>
+		<wu-notification-center
+			id=panel_id
+			popover
+			class=move || format!("vertical gap-2 m-0 inset-auto w-96 max-w-lvw max-h-[70vh] p-4 border bg-surface-1 border-surface-2 shadow-lg rounded-md {panel_class}")
+		>
+			<div class="horizontal vcenter gap-2">
+				<h2 class="grow font-bold">"Notifications"</h2>
+				<button class="btn" on:click=move |_| history.mark_all_read()>"Mark all read"</button>
+				<button class="btn" on:click=move |_| history.clear()>"Clear all"</button>
+			</div>
+			<Show
+				when=move || history.notifications.with(|notifications| !notifications.is_empty())
+				fallback=|| view! { <p class="py-4 text-center text-content-sideinfo">"No notifications"</p> }
+			>
+				<ul class="vertical gap-1 overflow-y-auto">
+					<For
+						each=move || history.notifications().into_iter().rev()
+						key=move |notification| (notification.id, notification.timestamp.to_bits(), notification.read)
+						let:notification
+					>
+						{
+							let (icon_class, color_class) = notification.level.icon_classes();
+							let time = web_sys::js_sys::Date::new(&notification.timestamp.into()).to_locale_time_string("default");
+							let id = notification.id;
+							view! {
+								<li
+									on:click=move |_| history.mark_read(id)
+									class="horizontal vcenter gap-2 p-2 rounded-md autohighlight"
+								>
+									<span class=format!("flex-none size-6 icon {icon_class} {color_class}") />
+									<div class="grow horizontal vcenter overflow-hidden">{notification.view.run()}</div>
+									<span class="flex-none text-xs text-content-sideinfo">{String::from(time)}</span>
+									<span class=format!("flex-none size-2 rounded-full {}", if notification.read { "invisible" } else { "bg-current" }) />
+								</li>
+							}
+						}
+					</For>
+				</ul>
+			</Show>
+		</wu-notification-center>
+	}
+}
+
+/// A type that allows modifying or cancelling a toast.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ToastHandle {
 	toasts_ref: RwSignal<Vec<ToastWithId>>,
 	toast_id: u64,
-	timeout_handle: TimeoutHandle,
+	history: Option<NotificationHistory>,
 }
 
 impl ToastHandle {
 	/// Cancels the toast.
 	pub fn cancel(&self) {
-		self.timeout_handle.clear();
-		self.toasts_ref.write().retain(|toast: &ToastWithId| toast.id != self.toast_id);
+		let mut removed_owner = None;
+		// the hook might be gone already
+		_ = self.toasts_ref.try_update(|toasts| {
+			toasts.retain(|toast: &ToastWithId| {
+				if toast.id == self.toast_id {
+					toast.timer.update_value(ToastTimer::pause);
+					removed_owner = Some(toast.owner.get_value());
+				}
+				toast.id != self.toast_id
+			});
+		});
+		// the toast's view still reads its state until it gets removed on the next render
+		if let Some(owner) = removed_owner {
+			request_animation_frame(move || owner.cleanup());
+		}
+	}
+
+	/// Replaces the contents of the toast, restarting its timeout.
+	pub fn update(&self, toast: Toast) {
+		let timeout = toast.timeout;
+		if let Some(entry) = self.entry() {
+			if let Some(history) = self.history {
+				history.record(self.toast_id, &toast);
+			}
+			entry.toast.set(toast);
+			entry.view_changed.notify();
+			entry.timer.update_value(move |timer| timer.restart(timeout));
+			self.sync_timer(&entry);
+		}
+	}
+
+	/// Changes the level of the toast.
+	pub fn set_level(&self, level: ToastLevel) {
+		if let Some(entry) = self.entry() {
+			entry.toast.update(move |toast| toast.level = level);
+			if let Some(history) = self.history {
+				history.notifications.update(|notifications| {
+					if let Some(notification) = notifications.iter_mut().find(|notification| notification.id == self.toast_id) {
+						notification.level = level;
+					}
+				});
+			}
+		}
+	}
+
+	/// Changes the progress of the toast, from `0.0` to `1.0`.
+	pub fn set_progress(&self, progress: f32) {
+		if let Some(entry) = self.entry() {
+			entry.toast.update(move |toast| toast.progress = Some(progress.clamp(0.0, 1.0)));
+		}
+	}
+
+	/// Extends the time the toast is displayed for.
+	pub fn extend(&self, duration: Duration) {
+		if let Some(entry) = self.entry() {
+			entry.timer.update_value(move |timer| timer.extend(duration));
+			self.sync_timer(&entry);
+		}
+	}
+
+	fn entry(&self) -> Option<ToastWithId> {
+		// the hook might be gone already
+		self.toasts_ref.try_with_untracked(|toasts| toasts.iter().find(|toast| toast.id == self.toast_id).cloned()).flatten()
+	}
+
+	/// Runs or pauses the timer depending on whether the toast is being interacted with or visible.
+	fn sync_timer(&self, entry: &ToastWithId) {
+		let handle = *self;
+		let is_hidden = document().hidden();
+		let is_pending = entry.toast.with_untracked(|toast| toast.pending);
+		entry.timer.update_value(move |timer| match timer.is_hovered || timer.is_focused || timer.is_queued || is_hidden || is_pending {
+			true => timer.pause(),
+			false => timer.resume(move || handle.cancel()),
+		});
 	}
 }
 
@@ -125,6 +451,17 @@ pub fn ToastHook<M>(
 	/// Toast container class.
 	#[prop(optional, into)]
 	container_class: Text,
+	/// Displays a bar showing the time left until the toast closes.
+	#[prop(optional)]
+	countdown: bool,
+	/// Maximum amount of toasts displayed at once.
+	///
+	/// The rest are queued and displayed once the earlier ones close.
+	#[prop(optional, into)]
+	max_visible: Option<usize>,
+	/// Records every displayed toast into a [`ToastHistory<M>`] context.
+	#[prop(optional)]
+	history: bool,
 	/// Children of the component.
 	children: Children,
 ) -> impl IntoView
@@ -134,23 +471,93 @@ where
 	// toast creation
 	let toast_id = RwSignal::new(0u64);
 	let toasts = RwSignal::new(Vec::default());
+	let history = history.then(|| NotificationHistory {
+		notifications: RwSignal::new(Vec::default()),
+	});
+	if let Some(history) = history {
+		provide_context(ToastHistory::<M>::new(history));
+	}
+	let toast_handle = move |toast_id| ToastHandle { toasts_ref: toasts, toast_id, history };
+	// toast state must outlive whichever component pushed the toast
+	let owner = Owner::current().expect("should be called inside a component");
 	provide_context(PushToast::<M>::new(Callback::new(move |toast: Toast| {
+		// a toast with the same key is already displayed, so replace it instead
+		let duplicate = toast.key.as_ref().and_then(|key| {
+			toasts.with_untracked(|toasts: &Vec<ToastWithId>| {
+				toasts
+					.iter()
+					.find(|entry| entry.toast.with_untracked(|toast| toast.key.as_ref() == Some(key)))
+					.copied()
+			})
+		});
+		if let Some(entry) = duplicate {
+			let toast_handle = toast_handle(entry.id);
+			toast_handle.update(toast);
+			entry.count.update(|count| *count += 1);
+			return toast_handle;
+		}
+
 		let id = toast_id.get_untracked();
-		let timeout_handle = set_timeout_with_handle(move || toasts.write().retain(|toast: &ToastWithId| toast.id != id), toast.timeout);
-		let Ok(timeout_handle) = timeout_handle else {
-			unreachable!("should be able to construct a timeout handle always");
-		};
-		let toast_handle = ToastHandle {
-			toasts_ref: toasts,
-			toast_id: id,
-			timeout_handle,
-		};
+		let toast_handle = toast_handle(id);
+		if let Some(history) = history {
+			history.record(id, &toast);
+		}
+		let mut timer = ToastTimer::new(toast.timeout);
+		timer.is_queued = max_visible.is_some_and(|max_visible| toasts.with_untracked(Vec::len) >= max_visible);
+		let toast_owner = owner.child();
+		let entry = toast_owner.with(|| {
+			let entry = ToastWithId {
+				id,
+				toast: RwSignal::new(toast),
+				view_changed: Trigger::new(),
+				timer: StoredValue::new(timer),
+				count: RwSignal::new(1),
+				owner: StoredValue::new(toast_owner.clone()),
+			};
+			// stops the timer once the toast or the whole hook goes away
+			on_cleanup(move || entry.timer.update_value(ToastTimer::pause));
+			entry
+		});
+		toast_handle.sync_timer(&entry);
 
-		toasts.update(move |toasts| toasts.push(ToastWithId { id, toast, timeout_handle }));
+		toasts.update(move |toasts| toasts.push(entry));
 		toast_id.update(|n| *n = n.overflowing_add(1).0);
 		toast_handle
 	})));
 
+	// queued toasts start their timers once they get displayed
+	Effect::new(move |_| {
+		let Some(max_visible) = max_visible else {
+			return;
+		};
+		for (idx, entry) in toasts.get().into_iter().enumerate() {
+			let is_queued = idx >= max_visible;
+			if entry.timer.with_value(|timer| timer.is_queued) != is_queued {
+				entry.timer.update_value(move |timer| timer.is_queued = is_queued);
+				toast_handle(entry.id).sync_timer(&entry);
+			}
+		}
+	});
+	let queued_count = Memo::new(move |_| {
+		let count = toasts.with(Vec::len);
+		max_visible.map_or(0, |max_visible| count.saturating_sub(max_visible))
+	});
+
+	// pause all timers while the page is in the background
+	_ = leptos_use::use_event_listener(document(), leptos::ev::visibilitychange, move |_| {
+		for entry in toasts.get_untracked() {
+			toast_handle(entry.id).sync_timer(&entry);
+		}
+	});
+
+	// the countdown bars are redrawn periodically while there are toasts
+	let countdown_tick = Trigger::new();
+	let leptos_use::utils::Pausable { pause, resume, .. } = leptos_use::use_interval_fn(move || countdown_tick.notify(), 100);
+	Effect::new(move |_| match countdown && toasts.with(|toasts| !toasts.is_empty()) {
+		true => resume(),
+		false => pause(),
+	});
+
 	let (position_class, radius_class) = match position {
 		Position::TopLeft => ("internal-tl", "last:rounded-br-(--wu-dynamic-toast-border-radius)"),
 		Position::Top => ("internal-t", "last:rounded-b-(--wu-dynamic-toast-border-radius)"),
@@ -162,75 +569,413 @@ where
 		Position::Left => ("internal-l", "first:rounded-tr-(--wu-dynamic-toast-border-radius) last:rounded-br-(--wu-dynamic-toast-border-radius)"),
 	};
 
+	// screen readers do not reliably announce live regions inserted along with their content,
+	// so the toasts' text is mirrored into live regions which are always present
+	let announcements = RwSignal::new(Vec::<(u64, &'static str, String)>::default());
+	let element_id_prefix = StoredValue::new(format!("wu-toast-{}", uuid::Uuid::new_v4()));
+	let announced = move |role: &'static str| {
+		announcements.with(|announcements| {
+			announcements
+				.iter()
+				.filter(|(_, announcement_role, _)| *announcement_role == role)
+				.map(|(id, _, text)| (*id, text.clone()))
+				.collect::<Vec<_>>()
+		})
+	};
+
+	let toast_view = move |entry: ToastWithId| {
+		let id = entry.id;
+		let toast_handle = toast_handle(id);
+		let toast = entry.toast;
+		let view_changed = entry.view_changed;
+		let level = Memo::new(move |_| toast.with(|toast| toast.level));
+		let progress = Memo::new(move |_| toast.with(|toast| toast.progress));
+		let dismissable = Memo::new(move |_| toast.with(|toast| toast.dismissable));
+		let pending = Memo::new(move |_| toast.with(|toast| toast.pending));
+		let count = entry.count;
+		let timer = entry.timer;
+		let set_hovered = move |is_hovered: bool| {
+			timer.update_value(move |timer| timer.is_hovered = is_hovered);
+			toast_handle.sync_timer(&entry);
+		};
+		let set_focused = move |is_focused: bool| {
+			timer.update_value(move |timer| timer.is_focused = is_focused);
+			toast_handle.sync_timer(&entry);
+		};
+		let element_id = element_id_prefix.with_value(|prefix| format!("{prefix}-{id}"));
+
+		// announce once displayed and every time the content gets replaced
+		Effect::new({
+			let element_id = element_id.clone();
+			move |_| {
+				view_changed.track();
+				let role = level.get().aria_role();
+				let element_id = element_id.clone();
+				// the content gets rendered in the meantime
+				request_animation_frame(move || {
+					let Some(text) = document().get_element_by_id(&element_id).and_then(|element| element.text_content()) else {
+						return;
+					};
+					let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+					_ = announcements.try_update(move |announcements| {
+						announcements.retain(|(announcement_id, ..)| *announcement_id != id);
+						announcements.push((id, role, text));
+					});
+				});
+			}
+		});
+		on_cleanup(move || _ = announcements.try_update(|announcements| announcements.retain(|(announcement_id, ..)| *announcement_id != id)));
+
+		view! {
+			<wu-toast
+				id=element_id
+				style=TOAST_STYLE
+				on:mouseenter=move |_| set_hovered(true)
+				on:mouseleave=move |_| set_hovered(false)
+				on:focusin=move |_| set_focused(true)
+				on:focusout=move |_| set_focused(false)
+				class=format!("vertical gap-1 max-w-lvw min-h-(--wu-dynamic-toast-min-height) p-(--wu-dynamic-toast-padding) motion-safe:transition motion-safe:starting:opacity-0 motion-safe:starting:scale-95 {radius_class}")
+			>
+				<div class="horizontal vcenter gap-4">
+					// icon
+					{move || match pending.get() {
+						true => view! { <span aria-hidden="true" class="flex-none loading" /> }.into_any(),
+						false => {
+							let (icon_class, color_class) = level.get().icon_classes();
+							view! { <span aria-hidden="true" class=format!("flex-none size-6 icon {icon_class} {color_class}") /> }.into_any()
+						},
+					}}
+					// content
+					<div class=move || format!("grow horizontal vcenter {class}")>
+						{move || {
+							// re-render only on content replacement, not on e.g. progress changes
+							view_changed.track();
+							toast.with_untracked(|toast| toast.view.clone()).run()
+						}}
+					</div>
+					// count
+					{move || (count.get() > 1).then(move || view! {
+						<span class="flex-none badge bg-current/10">{format!("×{}", count.get())}</span>
+					})}
+					// close
+					{move || dismissable.get().then(move || view! {
+						<button
+							aria-label="Dismiss"
+							class="flex-none btn-icon autohighlight size-6"
+							on:click=move |_| toast_handle.cancel()
+						>
+							<span class="icon i-o-x-mark"/>
+						</button>
+					})}
+				</div>
+				// actions
+				{move || {
+					view_changed.track();
+					let actions = toast.with_untracked(|toast| toast.actions.clone());
+					(!actions.is_empty()).then(move || view! {
+						<div class="horizontal hend gap-2">
+							{actions.into_iter().map(move |action| view! {
+								<button
+									class=if action.primary { "btn btn-primary" } else { "btn" }
+									on:click=move |_| {
+										action.callback.run(());
+										toast_handle.cancel();
+									}
+								>
+									{action.label}
+								</button>
+							}).collect_view()}
+						</div>
+					})
+				}}
+				// progress
+				{move || progress.get().map(move |progress| view! {
+					<progress class="w-full h-1" max="1" value=progress />
+				})}
+				// countdown
+				{countdown.then(move || view! {
+					<div
+						aria-hidden="true"
+						style=move || {
+							countdown_tick.track();
+							let fraction = timer.with_value(|timer| timer.fraction_left());
+							format!("width: {}%;", fraction * 100.0)
+						}
+						class="h-0.5 bg-current opacity-25"
+					/>
+				})}
+			</wu-toast>
+		}
+	};
+
 	view! {
 		{children()}
 		<wu-toasts class=move || format!("overlay cover z-9000 {container_class}")>
-			<ul class=format!("overlay w-fit {position_class}")>
+			<div class=format!("overlay w-fit vertical {position_class}")>
 				<For
-					each=move || toasts.get()
+					each=move || toasts.with(|toasts| toasts.iter().take(max_visible.unwrap_or(usize::MAX)).copied().collect::<Vec<_>>())
 					key=move |toast| toast.id
-					children=move |toast| {
-						let toast_handle = ToastHandle {
-							toasts_ref: toasts,
-							toast_id: toast.id,
-							timeout_handle: toast.timeout_handle,
-						};
-
-						view! {
-							<wu-toast
-								style="\
-									background-color: var(--wu-dynamic-toast-bg-color);\
-									border-color: var(--wu-dynamic-toast-border-color);\
-									border-width: var(--wu-dynamic-toast-border-width);\
-									box-shadow: var(--wu-dynamic-toast-shadow);\
-								"
-								class=format!("horizontal vcenter gap-4 max-w-lvw min-h-(--wu-dynamic-toast-min-height) p-(--wu-dynamic-toast-padding) {radius_class}")
-							>
-								// content
-								<div class=move || format!("grow {class}")>
-									{toast.view.run()}
-								</div>
-								// close
-								{toast.dismissable.then(move || view! {
-									<button
-										class="flex-none btn-icon autohighlight size-6"
-										on:click=move |_| toast_handle.cancel()
-									>
-										<span class="icon i-o-x-mark"/>
-									</button>
-								})}
-							</wu-toast>
-						}
-					}
+					children=toast_view
 				/>
-			</ul>
+				// queued
+				{move || (queued_count.get() > 0).then(move || view! {
+					<wu-toast
+						style=TOAST_STYLE
+						class=format!("horizontal hcenter p-(--wu-dynamic-toast-padding) {radius_class}")
+					>
+						<span class="text-sm">{move || format!("+{} more", queued_count.get())}</span>
+					</wu-toast>
+				})}
+			</div>
+			// announcements
+			<div role="status" aria-live="polite" class="sr-only">
+				<For each=move || announced("status") key=move |announcement| announcement.clone() let:announcement>
+					<p>{announcement.1}</p>
+				</For>
+			</div>
+			<div role="alert" class="sr-only">
+				<For each=move || announced("alert") key=move |announcement| announcement.clone() let:announcement>
+					<p>{announcement.1}</p>
+				</For>
+			</div>
 		</wu-toasts>
 	}
 }
 
-#[derive(Clone)]
-struct ToastWithId {
-	pub id: u64,
-	toast: Toast,
-	timeout_handle: TimeoutHandle,
+/// Shows a pending toast until the future resolves, then replaces it with
+/// a toast computed from the output.
+///
+/// # Example
+/// ```rust,ignore
+/// let push_toast = expect_context::<PushToast<MyApp>>();
+/// let result = wu::promise_toast(
+///     push_toast,
+///     "Saving...",
+///     save_document(document),
+///     |_| Toast::from_text(ToastLevel::Info, "Saved."),
+///     |err| Toast::from_text(ToastLevel::Error, format!("Failed to save: {err}")),
+/// ).await;
+/// ```
+pub fn promise_toast<M, T, E>(
+	push_toast: PushToast<M>,
+	pending_text: impl Into<Text>,
+	future: impl std::future::Future<Output = Result<T, E>>,
+	on_ok: impl FnOnce(&T) -> Toast,
+	on_err: impl FnOnce(&E) -> Toast,
+) -> impl std::future::Future<Output = Result<T, E>>
+where
+	M: Send + Sync + 'static,
+{
+	// the toast shows up eagerly, even before the future gets polled
+	let toast_handle = push_toast.run(Toast::from_text(ToastLevel::Info, pending_text).pending());
+	// a future dropped before resolving would leave the pending toast displayed forever
+	let mut cancel_guard = ToastCancelGuard(Some(toast_handle));
+
+	async move {
+		let result = future.await;
+		if let Some(toast_handle) = cancel_guard.0.take() {
+			toast_handle.update(match &result {
+				Ok(value) => on_ok(value),
+				Err(err) => on_err(err),
+			});
+		}
+		result
+	}
 }
 
-impl PartialEq for ToastWithId {
-	fn eq(&self, other: &Self) -> bool {
-		self.id == other.id
+/// Cancels the toast once dropped, unless the handle has been taken out.
+struct ToastCancelGuard(Option<ToastHandle>);
+
+impl Drop for ToastCancelGuard {
+	fn drop(&mut self) {
+		if let Some(toast_handle) = self.0.take() {
+			toast_handle.cancel();
+		}
 	}
 }
 
-impl std::ops::Deref for ToastWithId {
-	type Target = Toast;
+/// Shows a pending toast every time the action gets dispatched, then replaces
+/// it with a toast computed from the action's output once it finishes.
+///
+/// # Example
+/// ```rust,ignore
+/// let push_toast = expect_context::<PushToast<MyApp>>();
+/// let save = Action::new(move |document: &Document| save_document(document.clone()));
+/// wu::action_toast(push_toast, save, "Saving...", |result| match result {
+///     Ok(_) => Toast::from_text(ToastLevel::Info, "Saved."),
+///     Err(err) => Toast::from_text(ToastLevel::Error, format!("Failed to save: {err}")),
+/// });
+/// ```
+pub fn action_toast<M, I, O>(
+	push_toast: PushToast<M>,
+	action: Action<I, O>,
+	pending_text: impl Into<Text>,
+	on_finish: impl Fn(&O) -> Toast + Send + Sync + 'static,
+) where
+	M: Send + Sync + 'static,
+	I: Send + Sync + 'static,
+	O: Send + Sync + 'static,
+{
+	let pending_text = pending_text.into();
+	let toast_handle = StoredValue::new(None::<ToastHandle>);
+
+	Effect::watch(
+		move || action.pending().get(),
+		move |curr, past, _| match (curr, past) {
+			(true, Some(false) | None) => {
+				let pending_toast = Toast::from_text(ToastLevel::Info, pending_text).pending();
+				toast_handle.set_value(Some(push_toast.run(pending_toast)));
+			},
+			(false, Some(true)) => {
+				let Some(handle) = toast_handle.get_value() else {
+					return;
+				};
+				// the action might have been aborted, in which case there is no output to show
+				match action.value().with_untracked(|value| value.as_ref().map(&on_finish)) {
+					Some(toast) => handle.update(toast),
+					None => handle.cancel(),
+				}
+				toast_handle.set_value(None);
+			},
+			_ => {},
+		},
+		true,
+	);
+}
+
+impl ToastLevel {
+	/// Gets the icon and color classes of the level.
+	fn icon_classes(&self) -> (&'static str, &'static str) {
+		match self {
+			ToastLevel::Info => ("i-o-info-circle", ""),
+			ToastLevel::Success => ("i-o-check-circle", "icon-success-700 dark:icon-success-500"),
+			ToastLevel::Warn => ("i-o-exclamation-circle", "icon-warning-700 dark:icon-warning-500"),
+			ToastLevel::Error => ("i-o-exclamation-triangle", "icon-error-700 dark:icon-error-500"),
+			ToastLevel::Custom { icon_class, color_class, .. } => (icon_class, color_class),
+		}
+	}
 
-	fn deref(&self) -> &Self::Target {
-		&self.toast
+	/// Gets the live region role of the level.
+	///
+	/// Errors interrupt the screen reader, everything else waits for it to finish.
+	fn aria_role(&self) -> &'static str {
+		match self {
+			ToastLevel::Error | ToastLevel::Custom { assertive: true, .. } => "alert",
+			_ => "status",
+		}
 	}
 }
 
-impl std::ops::DerefMut for ToastWithId {
-	fn deref_mut(&mut self) -> &mut Self::Target {
-		&mut self.toast
+const TOAST_STYLE: &str = "\
+	background-color: var(--wu-dynamic-toast-bg-color);\
+	border-color: var(--wu-dynamic-toast-border-color);\
+	border-width: var(--wu-dynamic-toast-border-width);\
+	box-shadow: var(--wu-dynamic-toast-shadow);\
+";
+
+/// Gets the current time in milliseconds.
+fn now() -> f64 {
+	web_sys::js_sys::Date::now()
+}
+
+/// A toast timeout that can be paused and resumed.
+#[derive(Debug, Clone, Copy)]
+struct ToastTimer {
+	/// Total duration of the timer, in milliseconds.
+	total: f64,
+	/// Time left when the timer was last paused, in milliseconds.
+	remaining: f64,
+	/// When the timer was last resumed, if it is running.
+	resumed_at: Option<f64>,
+	/// Pending timeout, if it is running.
+	timeout_handle: Option<TimeoutHandle>,
+	/// Is the toast hovered over?
+	is_hovered: bool,
+	/// Does the toast contain focus?
+	is_focused: bool,
+	/// Is the toast waiting to be displayed?
+	is_queued: bool,
+}
+
+impl ToastTimer {
+	fn new(timeout: Duration) -> Self {
+		let timeout = timeout.as_millis() as f64;
+		Self {
+			total: timeout,
+			remaining: timeout,
+			resumed_at: None,
+			timeout_handle: None,
+			is_hovered: false,
+			is_focused: false,
+			is_queued: false,
+		}
+	}
+
+	/// Gets the time left, in milliseconds.
+	fn time_left(&self) -> f64 {
+		match self.resumed_at {
+			Some(resumed_at) => (self.remaining - (now() - resumed_at)).max(0.0),
+			None => self.remaining,
+		}
+	}
+
+	/// Gets the fraction of time left, from `0.0` to `1.0`.
+	fn fraction_left(&self) -> f64 {
+		match self.total > 0.0 {
+			true => self.time_left() / self.total,
+			false => 0.0,
+		}
+	}
+
+	fn pause(&mut self) {
+		self.remaining = self.time_left();
+		self.resumed_at = None;
+		if let Some(timeout_handle) = self.timeout_handle.take() {
+			timeout_handle.clear();
+		}
+	}
+
+	fn resume(&mut self, on_elapsed: impl FnOnce() + 'static) {
+		if self.resumed_at.is_some() {
+			return;
+		}
+		let Ok(timeout_handle) = set_timeout_with_handle(on_elapsed, Duration::from_millis(self.remaining as u64)) else {
+			unreachable!("should be able to construct a timeout handle always");
+		};
+		self.resumed_at = Some(now());
+		self.timeout_handle = Some(timeout_handle);
+	}
+
+	/// Restarts the timer with a new timeout, leaving it paused.
+	fn restart(&mut self, timeout: Duration) {
+		self.pause();
+		self.total = timeout.as_millis() as f64;
+		self.remaining = self.total;
+	}
+
+	/// Extends the timer, leaving it paused.
+	fn extend(&mut self, duration: Duration) {
+		self.pause();
+		self.total += duration.as_millis() as f64;
+		self.remaining += duration.as_millis() as f64;
+	}
+}
+
+#[derive(Clone, Copy)]
+struct ToastWithId {
+	id: u64,
+	toast: RwSignal<Toast>,
+	/// Notified when the toast's view gets replaced.
+	view_changed: Trigger,
+	timer: StoredValue<ToastTimer>,
+	/// How many times the toast has been pushed.
+	count: RwSignal<u32>,
+	/// Owns the toast's state, which gets freed once the toast is removed.
+	owner: StoredValue<Owner>,
+}
+
+impl PartialEq for ToastWithId {
+	fn eq(&self, other: &Self) -> bool {
+		self.id == other.id
 	}
 }
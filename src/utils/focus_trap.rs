@@ -1,9 +1,15 @@
-use std::{borrow::Cow, rc::Rc};
+use std::{borrow::Cow, cell::RefCell};
 
-use leptos::{prelude::*, ev::*};
-use leptos_use::*;
+use leptos::{ev::*, prelude::*};
 use web_sys::{wasm_bindgen::JsCast, Element, Node};
 
+thread_local! {
+	/// State of the trap focus context, shared between all focus traps so that they can nest.
+	static STATE: RefCell<Option<State>> = const { RefCell::new(None) };
+	/// Whether the global keyboard handler has been registered.
+	static IS_LISTENING: RefCell<bool> = const { RefCell::new(false) };
+}
+
 /// Creates a focus trap signal.
 ///
 /// # Example
@@ -12,15 +18,9 @@ use web_sys::{wasm_bindgen::JsCast, Element, Node};
 /// ```
 pub fn create_focus_trap(selector_id: impl Into<Cow<'static, str>>) -> (Callback<()>, Callback<()>) {
 	let selector_id: Cow<'static, str> = selector_id.into();
-	let (_, set_state) = signal_local(None::<State>);
-	let (unsub, set_unsub) = signal_local(None::<Rc<dyn Fn()>>);
+	let guards = StoredValue::new_local(Vec::<FocusTrapGuard>::default());
 
 	let add_trap = Callback::new(move |_| {
-		// find the origin if there exists one
-		// (it might not exist if the trap focus
-		// was caused programmatically)
-		let origin = document().active_element();
-
 		// find the currently active focus trap container target
 		let target = match document().get_element_by_id(&selector_id) {
 			Some(target) => target,
@@ -30,73 +30,112 @@ pub fn create_focus_trap(selector_id: impl Into<Cow<'static, str>>) -> (Callback
 			},
 		};
 
-		// find all focusable children inside the target
-		let children = get_focusable_children(&target);
+		let guard = push_focus_trap(target);
+		guards.update_value(move |guards| guards.push(guard));
+	});
 
-		// focus the first one in the target
-		if let Some((start, _)) = &children {
-			let _ = start
-				.dyn_ref::<web_sys::HtmlElement>()
-				.expect("node should be an html element according to the query selector")
-				.focus();
-		}
+	let pop_trap = Callback::new(move |_| {
+		// dropping the guard restores the previous trap
+		guards.update_value(move |guards| _ = guards.pop());
+	});
 
-		// register keyboard handling for the trap focus
-		set_unsub.set(Some(Rc::new(use_event_listener(document(), keydown, move |evt| focus_trap_event_handler(evt, &children)))));
+	(add_trap, pop_trap)
+}
 
-		// update the state
-		set_state.update(move |state| match state {
-			Some(state) => {
-				state.history.push(state.active.clone());
-				state.active = Event { target, origin };
-			},
-			None => {
-				*state = Some(State {
-					history: Vec::default(),
-					active: Event { origin, target },
-				});
-			},
-		});
+/// Traps focus inside of the target element until the returned guard is dropped.
+///
+/// The element that had focus before the trap was pushed gets focused again once the
+/// guard is dropped. Traps can be nested, in which case only the latest one is active.
+///
+/// # Note
+/// Should be called before the target element takes focus (e.g. before `show_modal()`)
+/// so that the correct element gets recorded as the origin.
+pub fn push_focus_trap(target: Element) -> FocusTrapGuard {
+	listen_for_focus_changes();
+
+	// find the origin if there exists one
+	// (it might not exist if the trap focus
+	// was caused programmatically)
+	let origin = document().active_element();
+
+	// focus the first focusable element in the target, unless something inside already has focus
+	let has_focus_inside = origin.as_ref().is_some_and(|origin| target.contains(Some(origin)));
+	if !has_focus_inside {
+		if let Some((start, _)) = get_focusable_children(&target) {
+			focus(&start);
+		}
+	}
+
+	// update the state
+	let event = Event { origin, target: target.clone() };
+	STATE.with_borrow_mut(move |state| match state {
+		Some(state) => {
+			let prev = std::mem::replace(&mut state.active, event);
+			state.history.push(prev);
+		},
+		None => {
+			*state = Some(State {
+				history: Vec::default(),
+				active: event,
+			});
+		},
 	});
 
-	let pop_trap = Callback::new(move |_| {
-		set_state.update(move |state_opt| {
-			if let Some(state) = state_opt {
-				// unsubscribe previous element
-				if let Some(unsub) = unsub.get() {
-					unsub();
-				}
+	FocusTrapGuard { target }
+}
 
-				// restore previous target and origin
-				let event = match state.history.pop() {
-					Some(event) => event,
-					None => {
-						*state_opt = None;
-						return;
-					},
-				};
-
-				// refetch the target's focusable children
-				let children = get_focusable_children(&event.target);
-
-				// focus the origin
-				if let Some(origin) = &state.active.origin {
-					let _ = origin
-						.dyn_ref::<web_sys::HtmlElement>()
-						.expect("node should be an html element according to the query selector")
-						.focus();
-				}
+/// A guard that keeps a focus trap active for as long as it lives.
+///
+/// Created by [`push_focus_trap`].
+#[must_use = "the focus trap is released as soon as the guard is dropped"]
+pub struct FocusTrapGuard {
+	target: Element,
+}
 
-				// subscribe to the new target again
-				set_unsub.set(Some(Rc::new(use_event_listener(event.target.clone(), keydown, move |evt| focus_trap_event_handler(evt, &children)))));
+impl Drop for FocusTrapGuard {
+	fn drop(&mut self) {
+		// release the state borrow before moving focus, since focusing can run arbitrary handlers
+		let restore_to = STATE.with_borrow_mut(|state_opt| {
+			let state = state_opt.as_mut()?;
+
+			// the trap is not the active one (e.g. a lower overlay closed first),
+			// so just remove it from the history
+			if state.active.target != self.target {
+				let idx = state.history.iter().position(|event| event.target == self.target)?;
+				let removed = state.history.remove(idx);
+				// the trap above it was opened from inside of the removed target, so inherit its origin
+				let above = state.history.get_mut(idx).unwrap_or(&mut state.active);
+				if above.origin.as_ref().is_some_and(|origin| removed.target.contains(Some(origin))) {
+					above.origin = removed.origin;
+				}
+				return None;
+			}
 
-				// update the target
-				state.active.target = event.target;
+			// restore previous target and origin
+			let origin = state.active.origin.take();
+			match state.history.pop() {
+				Some(event) => state.active = event,
+				None => *state_opt = None,
 			}
+			origin
 		});
-	});
 
-	(add_trap, pop_trap)
+		// focus the origin
+		if let Some(origin) = restore_to {
+			focus(&origin);
+		}
+	}
+}
+
+fn listen_for_focus_changes() {
+	IS_LISTENING.with_borrow_mut(|is_listening| {
+		if *is_listening {
+			return;
+		}
+		// the handler lives for the entire duration of the app
+		let _ = window_event_listener(keydown, focus_trap_event_handler);
+		*is_listening = true;
+	});
 }
 
 fn get_focusable_children(target: &Element) -> Option<(Node, Node)> {
@@ -112,32 +151,44 @@ fn get_focusable_children(target: &Element) -> Option<(Node, Node)> {
 	})
 }
 
-fn focus_trap_event_handler(evt: KeyboardEvent, children: &Option<(Node, Node)>) {
+fn focus(node: &Node) {
+	let _ = node
+		.dyn_ref::<web_sys::HtmlElement>()
+		.expect("node should be an html element according to the query selector")
+		.focus();
+}
+
+fn focus_trap_event_handler(evt: KeyboardEvent) {
 	// focus switching is done by tab and shift-tab
-	match (evt.shift_key(), &evt.code() == "Tab") {
-		(false, true) => {
-			if let Some((start, end)) = children {
-				if end == &evt.target().expect("keyboard event should have a target").dyn_into::<Node>().unwrap() {
-					let _ = start
-						.dyn_ref::<web_sys::HtmlElement>()
-						.expect("node should be an html element according to the query selector")
-						.focus();
-					evt.prevent_default();
-				}
+	if evt.code() != "Tab" {
+		return;
+	}
+
+	let Some(target) = STATE.with_borrow(|state| state.as_ref().map(|state| state.active.target.clone())) else {
+		return;
+	};
+
+	// refetch the children every time since the content of the target might have changed
+	let Some((start, end)) = get_focusable_children(&target) else {
+		evt.prevent_default();
+		return;
+	};
+	let focused = document().active_element().map(Node::from);
+	let is_outside = !focused.as_ref().is_some_and(|focused| target.contains(Some(focused)));
+
+	match evt.shift_key() {
+		false => {
+			if is_outside || focused.as_ref() == Some(&end) {
+				focus(&start);
+				evt.prevent_default();
 			}
 		},
-		(true, true) => {
-			if let Some((start, end)) = children {
-				if start == &evt.target().expect("keyboard event should have a target").dyn_into::<Node>().unwrap() {
-					let _ = end
-						.dyn_ref::<web_sys::HtmlElement>()
-						.expect("node should be an html element according to the query selector")
-						.focus();
-					evt.prevent_default();
-				}
+		true => {
+			if is_outside || focused.as_ref() == Some(&start) {
+				focus(&end);
+				evt.prevent_default();
 			}
 		},
-		_ => {},
 	}
 }
 
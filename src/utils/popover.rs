@@ -0,0 +1,33 @@
+use leptos::prelude::*;
+use web_sys::wasm_bindgen::JsCast;
+
+/// A popover anchored below the element toggling it, aligned to the element's right edge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct AnchoredPopover {
+	/// Id of the popover, used as the toggling element's `popovertarget`.
+	pub(crate) id: String,
+	/// Style of the toggling element.
+	pub(crate) anchor_style: String,
+	/// Style of the popover.
+	pub(crate) popover_style: String,
+}
+
+impl AnchoredPopover {
+	/// Creates a popover with a unique id starting with the given prefix.
+	pub(crate) fn new(prefix: &str) -> Self {
+		let id = format!("{prefix}-{}", uuid::Uuid::new_v4());
+		let anchor_name = format!("--{id}");
+		Self {
+			anchor_style: format!("anchor-name: {anchor_name};"),
+			popover_style: format!("position-anchor: {anchor_name}; inset: auto; top: anchor(bottom); right: anchor(right);"),
+			id,
+		}
+	}
+
+	/// Hides the popover if it is displayed.
+	pub(crate) fn hide(&self) {
+		if let Some(popover) = document().get_element_by_id(&self.id) {
+			_ = popover.unchecked_into::<web_sys::HtmlElement>().hide_popover();
+		}
+	}
+}
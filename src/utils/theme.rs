@@ -97,35 +97,16 @@ pub fn provide_theme_context() {
 }
 
 fn get_theme_from_local_storage() -> Theme {
-	match window().local_storage() {
-		Ok(storage) => match storage {
-			Some(storage) => match storage.get_item(THEME_NAME) {
-				Ok(item) => match item {
-					Some(item) => match serde_json::from_str::<'_, Theme>(&item) {
-						Ok(theme) => return theme,
-						Err(err) => log::error!("could not parse `{THEME_NAME}`: {err}"),
-					},
-					None => log::error!("could not get `{THEME_NAME}` from local storage"),
-				},
-				Err(err) => log::error!("error while getting `{THEME_NAME}` from local storage: {err:?}"),
-			},
-			None => log::error!("could not get local storage"),
-		},
-		Err(err) => log::error!("error while getting local storage: {err:?}"),
-	};
+	if let Some(item) = super::get_from_local_storage(THEME_NAME) {
+		match serde_json::from_str::<'_, Theme>(&item) {
+			Ok(theme) => return theme,
+			Err(err) => log::error!("could not parse `{THEME_NAME}`: {err}"),
+		}
+	}
 
 	Theme::Auto
 }
 
 fn set_theme_to_local_storage(new_theme: Theme) {
-	match window().local_storage() {
-		Ok(storage) => match storage {
-			Some(storage) => match storage.set_item(THEME_NAME, &serde_json::to_string(&new_theme).expect("should always be valid")) {
-				Ok(..) => log::trace!("successfully set `{THEME_NAME}` to local storage"),
-				Err(err) => log::error!("error while setting `{THEME_NAME}` to local storage: {err:?}"),
-			},
-			None => log::error!("could not get local storage"),
-		},
-		Err(err) => log::error!("error while getting local storage: {err:?}"),
-	};
+	super::set_to_local_storage(THEME_NAME, &serde_json::to_string(&new_theme).expect("should always be valid"));
 }
@@ -14,13 +14,10 @@ pub fn TabsRoute() -> impl IntoView {
             class="flex flex-row divide-x divide-surface-800"
             list_class="flex flex-col divide-y divide-surface-800 w-[300px] scroll-lock"
             item=#[allow(unused_parens)] (move |tab: wu::TabSignal<Tab>| {
-                let switch_active_tab = expect_context::<wu::SwitchActiveTab<Tab>>();
                 view! {
-                    <li class="w-full">
-                        <button on:click=move |_| switch_active_tab(tab.with(|tab| tab.id)) class="cover flex center font-bold btn">
-                            {move || tab.with(|tab| tab.name.clone())}
-                        </button>
-                    </li>
+                    <span class="cover flex center font-bold btn">
+                        {move || tab.with(|tab| tab.name.clone())}
+                    </span>
                 }
             })
             content=#[allow(unused_parens)] (move |tab: wu::TabSignal<Tab>| {